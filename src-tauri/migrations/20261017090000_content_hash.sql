@@ -0,0 +1,15 @@
+-- コンテンツハッシュによる重複排除
+
+-- アイテム単位のハッシュ（プライマリコンテンツ）と再コピー回数
+ALTER TABLE clipboard_items ADD COLUMN content_hash TEXT;
+ALTER TABLE clipboard_items ADD COLUMN loose_content_hash TEXT; -- 空白の差異を無視したハッシュ
+ALTER TABLE clipboard_items ADD COLUMN copy_count INTEGER NOT NULL DEFAULT 1;
+
+-- 形式単位のハッシュ
+ALTER TABLE clipboard_contents ADD COLUMN content_hash TEXT;
+
+CREATE INDEX idx_clipboard_items_content_hash ON clipboard_items(content_hash);
+CREATE INDEX idx_clipboard_items_loose_content_hash ON clipboard_items(loose_content_hash);
+CREATE INDEX idx_clipboard_contents_content_hash ON clipboard_contents(content_hash);
+
+-- 既存データのハッシュはアプリ起動時に計算される（Database::backfill_content_hashes）
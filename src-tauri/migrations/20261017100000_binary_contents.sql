@@ -0,0 +1,29 @@
+-- 画像などのバイナリ形式をBLOBで保存
+
+ALTER TABLE clipboard_contents ADD COLUMN content_blob BLOB;
+
+-- 全文検索はテキスト形式のみを対象にする
+DROP TRIGGER clipboard_contents_ai;
+DROP TRIGGER clipboard_contents_ad;
+DROP TRIGGER clipboard_contents_au;
+
+CREATE TRIGGER clipboard_contents_ai AFTER INSERT ON clipboard_contents
+WHEN new.content_blob IS NULL AND new.format NOT LIKE 'image/%' BEGIN
+    INSERT INTO clipboard_search(item_id, format, content) VALUES (new.item_id, new.format, new.content);
+END;
+
+CREATE TRIGGER clipboard_contents_ad AFTER DELETE ON clipboard_contents BEGIN
+    DELETE FROM clipboard_search WHERE item_id = old.item_id AND format = old.format;
+END;
+
+CREATE TRIGGER clipboard_contents_au AFTER UPDATE ON clipboard_contents BEGIN
+    DELETE FROM clipboard_search WHERE item_id = old.item_id AND format = old.format;
+    INSERT INTO clipboard_search(item_id, format, content)
+    SELECT new.item_id, new.format, new.content
+    WHERE new.content_blob IS NULL AND new.format NOT LIKE 'image/%';
+END;
+
+-- 既存の画像データを検索インデックスから除外
+DELETE FROM clipboard_search WHERE format LIKE 'image/%';
+
+-- 既存のdata URL形式の画像はアプリ起動時にBLOBへ変換される（Database::migrate_data_url_images）
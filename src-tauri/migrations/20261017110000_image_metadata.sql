@@ -0,0 +1,18 @@
+-- 画像形式のメタデータとサムネイル
+
+CREATE TABLE clipboard_images (
+    item_id TEXT NOT NULL,
+    format TEXT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    pixel_format TEXT NOT NULL,
+    byte_size INTEGER NOT NULL,
+    thumbnail BLOB,
+    thumbnail_width INTEGER,
+    thumbnail_height INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (item_id, format),
+    FOREIGN KEY (item_id) REFERENCES clipboard_items(id) ON DELETE CASCADE
+);
+
+-- 既存画像のメタデータはアプリ起動時に生成される（Database::backfill_image_metadata）
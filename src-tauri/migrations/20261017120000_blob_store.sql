@@ -0,0 +1,51 @@
+-- 大きなペイロードをディスク上のBLOBストアに保存
+
+-- BLOBストアのファイル（ハッシュ単位、参照数付き）
+CREATE TABLE blobs (
+    hash TEXT PRIMARY KEY,
+    size INTEGER NOT NULL,
+    ref_count INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- コンテンツからBLOBへの参照（blob_kindは 'text' または 'binary'）
+ALTER TABLE clipboard_contents ADD COLUMN blob_ref TEXT REFERENCES blobs(hash);
+ALTER TABLE clipboard_contents ADD COLUMN blob_kind TEXT;
+
+CREATE INDEX idx_clipboard_contents_blob_ref ON clipboard_contents(blob_ref);
+CREATE INDEX idx_blobs_ref_count ON blobs(ref_count);
+
+-- 全文検索の対象からBLOBストアに退避したバイナリを除外（退避したテキストは先頭部分を検索対象にする）
+DROP TRIGGER clipboard_contents_ai;
+DROP TRIGGER clipboard_contents_au;
+
+CREATE TRIGGER clipboard_contents_ai AFTER INSERT ON clipboard_contents
+WHEN new.content_blob IS NULL AND new.blob_kind IS NOT 'binary' AND new.format NOT LIKE 'image/%' BEGIN
+    INSERT INTO clipboard_search(item_id, format, content) VALUES (new.item_id, new.format, new.content);
+END;
+
+CREATE TRIGGER clipboard_contents_au AFTER UPDATE ON clipboard_contents BEGIN
+    DELETE FROM clipboard_search WHERE item_id = old.item_id AND format = old.format;
+    INSERT INTO clipboard_search(item_id, format, content)
+    SELECT new.item_id, new.format, new.content
+    WHERE new.content_blob IS NULL AND new.blob_kind IS NOT 'binary' AND new.format NOT LIKE 'image/%';
+END;
+
+-- 参照数の自動更新トリガー（アイテム削除時のカスケード削除でも実行される）
+CREATE TRIGGER clipboard_contents_blob_ai AFTER INSERT ON clipboard_contents
+WHEN new.blob_ref IS NOT NULL BEGIN
+    UPDATE blobs SET ref_count = ref_count + 1 WHERE hash = new.blob_ref;
+END;
+
+CREATE TRIGGER clipboard_contents_blob_ad AFTER DELETE ON clipboard_contents
+WHEN old.blob_ref IS NOT NULL BEGIN
+    UPDATE blobs SET ref_count = ref_count - 1 WHERE hash = old.blob_ref;
+END;
+
+CREATE TRIGGER clipboard_contents_blob_au AFTER UPDATE OF blob_ref ON clipboard_contents
+WHEN old.blob_ref IS NOT new.blob_ref BEGIN
+    UPDATE blobs SET ref_count = ref_count - 1 WHERE hash = old.blob_ref;
+    UPDATE blobs SET ref_count = ref_count + 1 WHERE hash = new.blob_ref;
+END;
+
+-- 参照数が0になったBLOBはアイテム削除・クリーンアップ時に削除される（Database::collect_garbage_blobs）
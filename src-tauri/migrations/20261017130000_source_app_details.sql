@@ -0,0 +1,9 @@
+-- コピー元アプリケーションの詳細情報
+
+ALTER TABLE clipboard_items ADD COLUMN source_executable TEXT;
+ALTER TABLE clipboard_items ADD COLUMN source_window_title TEXT;
+
+CREATE INDEX idx_clipboard_items_source_app ON clipboard_items(source_app);
+
+-- 検出していなかった既存データの仮の値は不明として扱う
+UPDATE clipboard_items SET source_app = NULL WHERE source_app = 'clipboard-rs';
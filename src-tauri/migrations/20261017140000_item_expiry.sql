@@ -0,0 +1,5 @@
+-- 一定時間後に自動削除するアイテム（機密データのプレースホルダーなど）
+
+ALTER TABLE clipboard_items ADD COLUMN expires_at INTEGER; -- Unixミリ秒、NULLは無期限
+
+CREATE INDEX idx_clipboard_items_expires_at ON clipboard_items(expires_at);
@@ -0,0 +1,6 @@
+-- 機密データ（APIキー・秘密鍵・カード番号など）として検出されたアイテム
+
+ALTER TABLE clipboard_items ADD COLUMN is_sensitive BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE clipboard_items ADD COLUMN sensitive_kind TEXT; -- 一致した検出ルール名
+
+CREATE INDEX idx_clipboard_items_is_sensitive ON clipboard_items(is_sensitive);
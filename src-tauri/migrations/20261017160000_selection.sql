@@ -0,0 +1,5 @@
+-- 取得元のセレクション（clipboard: 通常のクリップボード / primary: X11のPRIMARYセレクション）
+
+ALTER TABLE clipboard_items ADD COLUMN selection TEXT NOT NULL DEFAULT 'clipboard';
+
+CREATE INDEX idx_clipboard_items_selection ON clipboard_items(selection);
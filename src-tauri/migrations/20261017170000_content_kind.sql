@@ -0,0 +1,23 @@
+-- テキストの内容の種類（MIMEタイプとは別に保存する）
+-- kind: url / json / code など、kind_confidence: 確信度（0.0〜1.0）、kind_detail: 補足情報（コードの言語など）
+
+ALTER TABLE clipboard_items ADD COLUMN kind TEXT;
+ALTER TABLE clipboard_items ADD COLUMN kind_confidence REAL;
+ALTER TABLE clipboard_items ADD COLUMN kind_detail TEXT;
+
+CREATE INDEX idx_clipboard_items_kind ON clipboard_items(kind);
+
+-- 内容の判定結果を形式名にしていた既存データをtext/plainに戻す（種類は起動時に再判定する）
+UPDATE clipboard_items SET primary_format = 'text/plain'
+WHERE primary_format IN ('text/uri-list', 'application/x-file-path')
+  AND NOT EXISTS (
+    SELECT 1 FROM clipboard_contents cc
+    WHERE cc.item_id = clipboard_items.id AND cc.format = 'text/plain'
+  );
+
+UPDATE clipboard_contents SET format = 'text/plain'
+WHERE format IN ('text/uri-list', 'application/x-file-path')
+  AND NOT EXISTS (
+    SELECT 1 FROM clipboard_contents other
+    WHERE other.item_id = clipboard_contents.item_id AND other.format = 'text/plain'
+  );
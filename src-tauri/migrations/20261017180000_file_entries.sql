@@ -0,0 +1,17 @@
+-- ファイル・ファイルリストのアイテムに含まれる各ファイルの情報（取得時と再検証時に更新）
+
+CREATE TABLE clipboard_files (
+    item_id TEXT NOT NULL,
+    position INTEGER NOT NULL, -- ファイルリスト内の順番
+    path TEXT NOT NULL,        -- 絶対パス
+    file_exists BOOLEAN NOT NULL,
+    is_dir BOOLEAN NOT NULL DEFAULT FALSE,
+    size INTEGER,
+    modified_at INTEGER,       -- 更新日時（Unixミリ秒）
+    mime_type TEXT,
+    checked_at INTEGER NOT NULL, -- 最後に確認した時刻（Unixミリ秒）
+    PRIMARY KEY (item_id, position),
+    FOREIGN KEY (item_id) REFERENCES clipboard_items(id) ON DELETE CASCADE
+);
+
+-- 既存のファイルリストは再検証コマンドで解決される（revalidate_item_files）
@@ -0,0 +1,25 @@
+-- HTML・RTFから派生させたテキスト（元のアプリが提供した形式ではない）
+
+ALTER TABLE clipboard_contents ADD COLUMN is_derived BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 全文検索にはマークアップを含むHTML・RTFの代わりに派生させたテキストを使う
+DROP TRIGGER clipboard_contents_ai;
+DROP TRIGGER clipboard_contents_au;
+
+CREATE TRIGGER clipboard_contents_ai AFTER INSERT ON clipboard_contents
+WHEN new.content_blob IS NULL AND new.blob_kind IS NOT 'binary' AND new.format NOT LIKE 'image/%'
+     AND new.format NOT IN ('text/html', 'text/rtf') BEGIN
+    INSERT INTO clipboard_search(item_id, format, content) VALUES (new.item_id, new.format, new.content);
+END;
+
+CREATE TRIGGER clipboard_contents_au AFTER UPDATE ON clipboard_contents BEGIN
+    DELETE FROM clipboard_search WHERE item_id = old.item_id AND format = old.format;
+    INSERT INTO clipboard_search(item_id, format, content)
+    SELECT new.item_id, new.format, new.content
+    WHERE new.content_blob IS NULL AND new.blob_kind IS NOT 'binary' AND new.format NOT LIKE 'image/%'
+      AND new.format NOT IN ('text/html', 'text/rtf');
+END;
+
+DELETE FROM clipboard_search WHERE format IN ('text/html', 'text/rtf');
+
+-- 既存のHTML・RTFのテキストはアプリ起動時に派生させる（Database::backfill_derived_texts）
@@ -0,0 +1,4 @@
+-- ClipOneから書き戻して使用した記録（コピー元としての再コピー回数とは別に数える）
+
+ALTER TABLE clipboard_items ADD COLUMN last_used_at INTEGER; -- Unixミリ秒、NULLは未使用
+ALTER TABLE clipboard_items ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0;
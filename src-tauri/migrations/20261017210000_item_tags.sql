@@ -0,0 +1,10 @@
+-- アイテムに付けるタグ（キャプチャルールで自動的に付ける）
+
+CREATE TABLE clipboard_tags (
+    item_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (item_id, tag),
+    FOREIGN KEY (item_id) REFERENCES clipboard_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_clipboard_tags_tag ON clipboard_tags(tag);
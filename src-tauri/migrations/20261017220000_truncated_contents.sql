@@ -0,0 +1,4 @@
+-- サイズ上限により切り詰めたコンテンツ（data_sizeは切り詰めた後のサイズ）
+
+ALTER TABLE clipboard_contents ADD COLUMN is_truncated BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE clipboard_contents ADD COLUMN original_size INTEGER; -- 切り詰める前のサイズ（バイト）
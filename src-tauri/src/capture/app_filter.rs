@@ -0,0 +1,197 @@
+use crate::database::SourceApp;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// アプリ別キャプチャルールの動作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppRuleAction {
+    /// 記録する
+    #[default]
+    Allow,
+    /// 記録しない
+    Block,
+}
+
+/// ルールが照合するコピー元アプリの項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppRuleField {
+    /// 実行ファイル名（フルパスまたはファイル名、大文字小文字を区別しない）
+    Executable,
+    /// WM_CLASS（大文字小文字を区別しない）
+    WmClass,
+    /// ウィンドウタイトルの正規表現
+    WindowTitle,
+}
+
+/// アプリ別キャプチャルール
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppCaptureRule {
+    #[serde(default)]
+    pub id: String,
+    pub action: AppRuleAction,
+    pub field: AppRuleField,
+    pub pattern: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl AppCaptureRule {
+    /// ルールの内容を検証（空のパターンや不正な正規表現を拒否）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.pattern.trim().is_empty() {
+            return Err("ルールのパターンが空です".to_string());
+        }
+        if self.field == AppRuleField::WindowTitle {
+            compile(&self.pattern)
+                .map_err(|e| format!("ウィンドウタイトルの正規表現が不正です: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// コピー元アプリがこのルールに一致するか（検出できなかった項目には一致しない）
+    ///
+    /// ウィンドウタイトルはコンパイル済みの正規表現で照合する。
+    fn matches(&self, title_regex: Option<&Regex>, source: &SourceApp) -> bool {
+        match self.field {
+            AppRuleField::Executable => source.executable.as_deref().is_some_and(|executable| {
+                let file_name = std::path::Path::new(executable)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or(executable);
+                executable.eq_ignore_ascii_case(&self.pattern)
+                    || file_name.eq_ignore_ascii_case(&self.pattern)
+            }),
+            AppRuleField::WmClass => source
+                .name
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(&self.pattern)),
+            AppRuleField::WindowTitle => match (title_regex, source.window_title.as_deref()) {
+                (Some(regex), Some(title)) => regex.is_match(title),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// 正規表現をコンパイルしたアプリ別キャプチャルール（設定の変更時に作り直す）
+pub struct AppFilter {
+    rules: Vec<(AppCaptureRule, Option<Regex>)>,
+    default_action: AppRuleAction,
+}
+
+impl AppFilter {
+    /// 有効なルールの正規表現をコンパイル（不正なルールは警告して無視）
+    pub fn new(rules: &[AppCaptureRule], default_action: AppRuleAction) -> Self {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| {
+                if rule.field != AppRuleField::WindowTitle {
+                    return Some((rule.clone(), None));
+                }
+                match compile(&rule.pattern) {
+                    Ok(regex) => Some((rule.clone(), Some(regex))),
+                    Err(e) => {
+                        warn!(
+                            "アプリ別キャプチャルールが不正です ({}): {}",
+                            rule.pattern, e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self {
+            rules,
+            default_action,
+        }
+    }
+
+    /// ルールを上から順に評価し、最初に一致したルールの動作を返す（一致しなければ既定の動作）
+    pub fn evaluate(&self, source: &SourceApp) -> AppRuleAction {
+        self.rules
+            .iter()
+            .find(|(rule, regex)| rule.matches(regex.as_ref(), source))
+            .map(|(rule, _)| rule.action)
+            .unwrap_or(self.default_action)
+    }
+}
+
+/// ウィンドウタイトルの正規表現をコンパイル（大文字小文字を区別しない）
+fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(field: AppRuleField, pattern: &str) -> AppCaptureRule {
+        AppCaptureRule {
+            id: String::new(),
+            action: AppRuleAction::Block,
+            field,
+            pattern: pattern.to_string(),
+            enabled: true,
+        }
+    }
+
+    fn source(name: &str, title: &str) -> SourceApp {
+        SourceApp {
+            name: Some(name.to_string()),
+            window_title: Some(title.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn window_title_rules_match_case_insensitively() {
+        let filter = AppFilter::new(
+            &[rule(AppRuleField::WindowTitle, "private browsing$")],
+            AppRuleAction::Allow,
+        );
+
+        assert_eq!(
+            filter.evaluate(&source("firefox", "Example — Private Browsing")),
+            AppRuleAction::Block
+        );
+        assert_eq!(
+            filter.evaluate(&source("firefox", "Example")),
+            AppRuleAction::Allow
+        );
+    }
+
+    #[test]
+    fn invalid_and_disabled_rules_are_skipped() {
+        let disabled = AppCaptureRule {
+            enabled: false,
+            ..rule(AppRuleField::WmClass, "keepassxc")
+        };
+        let filter = AppFilter::new(
+            &[
+                rule(AppRuleField::WindowTitle, "("),
+                disabled,
+                rule(AppRuleField::WmClass, "1Password"),
+            ],
+            AppRuleAction::Allow,
+        );
+
+        assert_eq!(
+            filter.evaluate(&source("keepassxc", "(")),
+            AppRuleAction::Allow
+        );
+        assert_eq!(
+            filter.evaluate(&source("1password", "Vault")),
+            AppRuleAction::Block
+        );
+        assert!(rule(AppRuleField::WindowTitle, "(").validate().is_err());
+    }
+}
@@ -0,0 +1,492 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// 分類に使う最大バイト数（巨大なテキストで時間がかからないように）
+const MAX_CLASSIFY_BYTES: usize = 64 * 1024;
+
+/// JSONとして全体を検証する最大バイト数
+const MAX_JSON_PARSE_BYTES: usize = 1024 * 1024;
+
+/// テキストの内容の種類（MIMEタイプとは別に保存する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    PlainText,
+    Url,
+    Email,
+    Phone,
+    Uuid,
+    IpAddress,
+    Color,
+    FilePath,
+    DataUrl,
+    Json,
+    Yaml,
+    Xml,
+    Html,
+    Rtf,
+    Sql,
+    Markdown,
+    Code,
+}
+
+impl ContentKind {
+    /// データベースに保存する値
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContentKind::PlainText => "plain_text",
+            ContentKind::Url => "url",
+            ContentKind::Email => "email",
+            ContentKind::Phone => "phone",
+            ContentKind::Uuid => "uuid",
+            ContentKind::IpAddress => "ip_address",
+            ContentKind::Color => "color",
+            ContentKind::FilePath => "file_path",
+            ContentKind::DataUrl => "data_url",
+            ContentKind::Json => "json",
+            ContentKind::Yaml => "yaml",
+            ContentKind::Xml => "xml",
+            ContentKind::Html => "html",
+            ContentKind::Rtf => "rtf",
+            ContentKind::Sql => "sql",
+            ContentKind::Markdown => "markdown",
+            ContentKind::Code => "code",
+        }
+    }
+}
+
+/// 分類結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Classification {
+    pub kind: ContentKind,
+    pub confidence: f64,        // 0.0〜1.0
+    pub detail: Option<String>, // 補足情報（コードの言語、IPのバージョン、色の表記など）
+}
+
+impl Classification {
+    fn new(kind: ContentKind, confidence: f64) -> Self {
+        Self {
+            kind,
+            confidence,
+            detail: None,
+        }
+    }
+
+    fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// 種類に対応するMIMEタイプ（形式の指定がないテキストを保存する場合に使用）
+    pub fn mime_type(&self) -> String {
+        match self.kind {
+            ContentKind::Html => "text/html".to_string(),
+            ContentKind::Rtf => "text/rtf".to_string(),
+            ContentKind::DataUrl => self
+                .detail
+                .clone()
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+            _ => "text/plain".to_string(),
+        }
+    }
+}
+
+fn regex(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("分類用の正規表現が不正です")
+}
+
+fn regex_ci(pattern: &str) -> Regex {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .expect("分類用の正規表現が不正です")
+}
+
+static URL: LazyLock<Regex> =
+    LazyLock::new(|| regex_ci(r"^(?:https?|ftp|file)://[^\s/?#]+[^\s]*$|^mailto:[^\s]+$"));
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"^[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}$"));
+static PHONE: LazyLock<Regex> = LazyLock::new(|| regex(r"^\+?\(?\d[\d\s().-]{5,}\d$"));
+static DATE: LazyLock<Regex> = LazyLock::new(|| regex(r"^\d{4}[-/.]\d{1,2}[-/.]\d{1,2}$"));
+static UUID: LazyLock<Regex> = LazyLock::new(|| {
+    regex(r"^\{?[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\}?$")
+});
+static HEX_COLOR: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"^#(?:[0-9a-fA-F]{3,4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$"));
+static FUNC_COLOR: LazyLock<Regex> = LazyLock::new(|| {
+    regex_ci(
+        r"^(rgb|hsl)a?\(\s*\d{1,3}(?:\.\d+)?%?\s*[,\s]\s*\d{1,3}(?:\.\d+)?%?\s*[,\s]\s*\d{1,3}(?:\.\d+)?%?\s*(?:[,/]\s*(?:0|1|0?\.\d+|\d{1,3}%)\s*)?\)$",
+    )
+});
+static XML_ELEMENT: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"^<([A-Za-z_][\w:.-]*)[^>]*>[\s\S]*</([A-Za-z_][\w:.-]*)>$"));
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    regex_ci(
+        r"<!doctype html|<html[\s>]|<(?:body|head|div|span|p|a|table|ul|ol|li|br|img|h[1-6])[\s/>]",
+    )
+});
+static SQL: LazyLock<Regex> = LazyLock::new(|| {
+    regex_ci(
+        r"^\s*(?:select\s[\s\S]+\sfrom\s|insert\s+into\s|update\s+\S+\s+set\s|delete\s+from\s|create\s+(?:or\s+replace\s+)?(?:unique\s+)?(?:table|index|view|trigger)\s|alter\s+table\s|drop\s+(?:table|index|view)\s|with\s+\w+\s+as\s*\()",
+    )
+});
+static YAML_KEY: LazyLock<Regex> =
+    LazyLock::new(|| regex(r#"^\s*(?:- )?["']?[\w.-]+["']?:(?:\s|$)"#));
+static YAML_LIST: LazyLock<Regex> = LazyLock::new(|| regex(r"^\s*- \S"));
+static MD_HEADING: LazyLock<Regex> = LazyLock::new(|| regex(r"^#{1,6}\s+\S"));
+static MD_LIST: LazyLock<Regex> = LazyLock::new(|| regex(r"^\s*(?:[-*+]|\d+\.)\s+\S"));
+static MD_INLINE: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"\[[^\]]+\]\([^)\s]+\)|\*\*[^*]+\*\*|`[^`\n]+`|^>\s"));
+static WINDOWS_PATH: LazyLock<Regex> =
+    LazyLock::new(|| regex(r#"^(?:[A-Za-z]:\\|\\\\[^\\/:*?"<>|\s]+\\)[^:*?"<>|]*$"#));
+
+/// 言語ごとの特徴的なパターン（言語名, パターン一覧）
+const CODE_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "rust",
+        &[
+            r"\bfn\s+\w+\s*[<(]",
+            r"\blet\s+mut\s",
+            r"\bimpl\b",
+            r"\bpub\s+(?:fn|struct|enum|mod)\b",
+            r"\buse\s+\w+(?:::\w+)+",
+            r"\w+!\(",
+            r"#\[derive\(",
+            r"&mut\s|&self\b",
+        ],
+    ),
+    (
+        "python",
+        &[
+            r"(?m)^\s*def\s+\w+\(.*\):\s*$",
+            r"(?m)^\s*(?:from\s+[\w.]+\s+)?import\s+\w+",
+            r"\bself\.",
+            r"(?m)^\s*elif\b",
+            r"(?m)^\s*class\s+\w+(?:\(.*\))?:\s*$",
+            r"\bNone\b|\bTrue\b|\bFalse\b",
+            r"__\w+__",
+        ],
+    ),
+    (
+        "typescript",
+        &[
+            r":\s*(?:string|number|boolean|void|any|unknown)\b",
+            r"\binterface\s+\w+\s*\{",
+            r"\btype\s+\w+\s*=",
+            r"\bimport\s+(?:type\s+)?\{[^}]*\}\s+from\s",
+            r"\bexport\s+(?:default\s+)?(?:function|const|class|interface|type)\b",
+        ],
+    ),
+    (
+        "javascript",
+        &[
+            r"\bfunction\s+\w*\s*\(",
+            r"\b(?:const|let|var)\s+\w+\s*=",
+            r"=>\s*[{(]?",
+            r"\bconsole\.\w+\(",
+            r"\brequire\(",
+            r"\b(?:document|window)\.",
+        ],
+    ),
+    (
+        "go",
+        &[
+            r"(?m)^package\s+\w+",
+            r"\bfunc\s+(?:\(\w+\s+\*?\w+\)\s*)?\w+\(",
+            r":=",
+            r"\bfmt\.\w+\(",
+            r"\bgo\s+func\b|\bchan\b|\bdefer\b",
+        ],
+    ),
+    (
+        "java",
+        &[
+            r"\bpublic\s+(?:static\s+)?(?:class|void|final)\b",
+            r"\bSystem\.out\.",
+            r"\bprivate\s+\w+(?:<[^>]*>)?\s+\w+\s*[;=]",
+            r"@Override\b",
+            r"\bnew\s+\w+(?:<[^>]*>)?\(",
+        ],
+    ),
+    (
+        "csharp",
+        &[
+            r"\busing\s+System",
+            r"\bnamespace\s+[\w.]+",
+            r"\bpublic\s+(?:async\s+)?(?:Task|void|string|int)\b",
+            r"\bConsole\.Write",
+            r"\bvar\s+\w+\s*=",
+        ],
+    ),
+    (
+        "c",
+        &[
+            r"(?m)^\s*#include\s*[<\x22]",
+            r"\bint\s+main\s*\(",
+            r"\bprintf\(|\bmalloc\(|\bsizeof\(",
+            r"(?m)^\s*#define\s",
+            r"\bstd::|\bcout\s*<<",
+        ],
+    ),
+    (
+        "shell",
+        &[
+            r"^#!/(?:usr/)?bin/(?:env\s+)?(?:ba|z)?sh",
+            r"(?m)^\s*(?:sudo|echo|export|cd|apt|npm|cargo|git)\s",
+            r"\$\(|\$\{\w+\}",
+            r"(?m)^\s*(?:fi|done|esac)\s*$",
+            r"\|\s*(?:grep|awk|sed|xargs)\b",
+        ],
+    ),
+    (
+        "css",
+        &[
+            r"(?m)^\s*[.#]?[\w-]+(?:\s*[,>+~]?\s*[.#]?[\w-]+)*\s*\{",
+            r"(?m)^\s*[\w-]+\s*:\s*[^;]+;\s*$",
+            r"@media\b|@import\b|@keyframes\b",
+        ],
+    ),
+];
+
+static CODE_LANGUAGES: LazyLock<Vec<(&'static str, Vec<Regex>)>> = LazyLock::new(|| {
+    CODE_SIGNATURES
+        .iter()
+        .map(|(language, patterns)| (*language, patterns.iter().map(|p| regex(p)).collect()))
+        .collect()
+});
+
+/// テキストの内容を分類する
+pub fn classify(text: &str) -> Classification {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Classification::new(ContentKind::PlainText, 1.0);
+    }
+
+    // 先頭のプレフィックスで確定できる形式
+    if let Some(classification) = classify_prefixed(trimmed) {
+        return classification;
+    }
+
+    // JSONは巨大でも全体を検証する（切り詰めると構文が壊れるため）
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && trimmed.len() <= MAX_JSON_PARSE_BYTES
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return Classification::new(ContentKind::Json, 1.0);
+    }
+
+    let sample = crate::database::truncate_to_char_boundary(trimmed, MAX_CLASSIFY_BYTES);
+    let truncated = sample.len() < trimmed.len();
+
+    if !sample.contains('\n') {
+        if let Some(classification) = classify_single_line(sample) {
+            return classification;
+        }
+    }
+
+    if let Some(classification) = classify_markup(sample, truncated) {
+        return classification;
+    }
+
+    if SQL.is_match(sample) {
+        return Classification::new(ContentKind::Sql, 0.85);
+    }
+
+    classify_structured(sample).unwrap_or_else(|| Classification::new(ContentKind::PlainText, 0.5))
+}
+
+/// data URL・RTF
+fn classify_prefixed(text: &str) -> Option<Classification> {
+    if let Some(rest) = text.strip_prefix("data:") {
+        let (header, _) = rest.split_once(',')?;
+        let mime = header.split(';').next().unwrap_or_default();
+        let mime = if mime.is_empty() { "text/plain" } else { mime };
+        return Some(Classification::new(ContentKind::DataUrl, 1.0).with_detail(mime));
+    }
+
+    if text.starts_with("{\\rtf") {
+        return Some(Classification::new(ContentKind::Rtf, 1.0));
+    }
+
+    None
+}
+
+/// 1行のテキスト（URL・メール・UUID・IP・色・パス・電話番号）
+fn classify_single_line(line: &str) -> Option<Classification> {
+    if UUID.is_match(line) {
+        return Some(Classification::new(ContentKind::Uuid, 1.0));
+    }
+
+    if let Some(version) = ip_version(line) {
+        return Some(Classification::new(ContentKind::IpAddress, 1.0).with_detail(version));
+    }
+
+    if URL.is_match(line) {
+        return Some(Classification::new(ContentKind::Url, 0.95));
+    }
+
+    if EMAIL.is_match(line) {
+        return Some(Classification::new(ContentKind::Email, 0.95));
+    }
+
+    if HEX_COLOR.is_match(line) {
+        return Some(Classification::new(ContentKind::Color, 0.9).with_detail("hex"));
+    }
+    if let Some(captures) = FUNC_COLOR.captures(line) {
+        let notation = captures[1].to_ascii_lowercase();
+        return Some(Classification::new(ContentKind::Color, 0.95).with_detail(notation));
+    }
+
+    if let Some(classification) = classify_path(line) {
+        return Some(classification);
+    }
+
+    // 区切りのない数字の並びや日付は電話番号として扱わない
+    if PHONE.is_match(line) && !DATE.is_match(line) {
+        let digits = line.chars().filter(char::is_ascii_digit).count();
+        let has_separator = line.starts_with('+') || line.contains([' ', '-', '(', '.']);
+        if has_separator && (7..=15).contains(&digits) {
+            return Some(Classification::new(ContentKind::Phone, 0.8));
+        }
+    }
+
+    None
+}
+
+/// IPアドレス（CIDR表記を含む）のバージョン
+fn ip_version(text: &str) -> Option<&'static str> {
+    let (address, prefix) = match text.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (text, None),
+    };
+
+    let ip: IpAddr = address.parse().ok()?;
+    let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+    if let Some(prefix) = prefix {
+        if !prefix.parse::<u8>().is_ok_and(|p| p <= max_prefix) {
+            return None;
+        }
+    }
+
+    Some(if ip.is_ipv4() { "v4" } else { "v6" })
+}
+
+/// ファイルパス（形式が正しいもののみ、実在すれば確信度を上げる）
+fn classify_path(line: &str) -> Option<Classification> {
+    let is_windows = WINDOWS_PATH.is_match(line);
+    let is_unix = line.starts_with('/') || line.starts_with("~/");
+    if !is_windows && !is_unix {
+        return None;
+    }
+
+    // 「/」だけ、区切りの連続、前後の空白など、パスとして不自然なものは除外
+    let body = line.trim_start_matches(['/', '~', '\\']);
+    if body.is_empty() || line.ends_with(' ') || line.contains("//") && !is_windows {
+        return None;
+    }
+
+    let expanded = match line.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+        None => Some(Path::new(line).to_path_buf()),
+    };
+    let exists = expanded.is_some_and(|path| path.exists());
+
+    let confidence = if exists { 1.0 } else { 0.6 };
+    let detail = if exists { "exists" } else { "missing" };
+    Some(Classification::new(ContentKind::FilePath, confidence).with_detail(detail))
+}
+
+/// XML・HTML
+fn classify_markup(text: &str, truncated: bool) -> Option<Classification> {
+    if !text.starts_with('<') {
+        return None;
+    }
+
+    if text.starts_with("<?xml") {
+        return Some(Classification::new(ContentKind::Xml, 1.0));
+    }
+
+    if HTML_TAG.is_match(text) {
+        return Some(Classification::new(ContentKind::Html, 0.9));
+    }
+
+    // ルート要素の開始タグと終了タグが対になっていればXML
+    if let Some(captures) = XML_ELEMENT.captures(text) {
+        if captures[1] == captures[2] {
+            return Some(Classification::new(ContentKind::Xml, 0.85));
+        }
+    }
+
+    truncated.then(|| Classification::new(ContentKind::Xml, 0.5))
+}
+
+/// 複数行の構造化テキスト（Markdown・YAML・ソースコード）
+fn classify_structured(text: &str) -> Option<Classification> {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    if let Some(classification) = classify_code(text) {
+        return Some(classification);
+    }
+
+    // Markdown: 見出し・コードフェンス・リンクなどの記法
+    let fences = lines
+        .iter()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count();
+    let headings = lines
+        .iter()
+        .filter(|line| MD_HEADING.is_match(line))
+        .count();
+    let list_items = lines.iter().filter(|line| MD_LIST.is_match(line)).count();
+    let inline = lines.iter().filter(|line| MD_INLINE.is_match(line)).count();
+    let markdown_signals = headings * 2 + fences + inline + usize::from(list_items >= 2);
+    if markdown_signals >= 2 && (headings > 0 || fences >= 2 || inline > 0) {
+        let confidence = (0.5 + markdown_signals as f64 * 0.1).min(0.9);
+        return Some(Classification::new(ContentKind::Markdown, confidence));
+    }
+
+    // YAML: 「key: value」や「- item」の行が大半を占める
+    let yaml_lines = lines
+        .iter()
+        .filter(|line| YAML_KEY.is_match(line) || YAML_LIST.is_match(line))
+        .count();
+    let has_key = lines.iter().any(|line| YAML_KEY.is_match(line));
+    if lines.len() >= 2 && has_key {
+        let ratio = yaml_lines as f64 / lines.len() as f64;
+        if text.starts_with("---") || ratio >= 0.8 {
+            return Some(Classification::new(
+                ContentKind::Yaml,
+                (0.5 + ratio * 0.4).min(0.9),
+            ));
+        }
+    }
+
+    None
+}
+
+/// ソースコード（言語ごとの特徴パターンの一致数で判定）
+fn classify_code(text: &str) -> Option<Classification> {
+    let (language, score) = CODE_LANGUAGES
+        .iter()
+        .map(|(language, patterns)| {
+            let score = patterns.iter().filter(|regex| regex.is_match(text)).count();
+            (*language, score)
+        })
+        .max_by_key(|(_, score)| *score)?;
+
+    if score < 2 {
+        return None;
+    }
+
+    let confidence = (0.5 + score as f64 * 0.1).min(0.95);
+    Some(Classification::new(ContentKind::Code, confidence).with_detail(language))
+}
@@ -0,0 +1,105 @@
+use crate::capture::CaptureEvent;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 最初のイベントから統合を打ち切るまでの最大待ち時間（クリップボードを更新し続けるアプリ対策）
+const MAX_COALESCE_WAIT: Duration = Duration::from_secs(2);
+
+/// プライマリ形式の優先順位（小さいほど優先、取得時の Text > Files > Image > RTF > HTML > 追加形式 と同じ）
+fn format_rank(format: &str) -> u8 {
+    match format {
+        "text/plain" => 0,
+        "application/x-file-list" => 1,
+        "image/png" => 2,
+        "text/rtf" => 3,
+        "text/html" => 4,
+        _ => 5,
+    }
+}
+
+/// 統合待ちのイベント
+struct Pending {
+    event: CaptureEvent,
+    first_at: Instant,
+    flush_at: Instant,
+}
+
+/// 短時間に連続したクリップボード変更を1つのアイテムにまとめる
+///
+/// テキスト・HTML・画像を別々に設定するアプリでは変更通知が複数回届くため、
+/// 変更が落ち着くまで待ってから全形式を統合したアイテムとして保存する。
+#[derive(Default)]
+pub struct Coalescer {
+    pending: Option<Pending>,
+}
+
+impl Coalescer {
+    /// イベントを統合待ちに追加し、統合できなかった直前のイベントがあれば返す
+    pub fn push(&mut self, event: CaptureEvent, window: Duration) -> Option<CaptureEvent> {
+        let now = Instant::now();
+
+        if let Some(pending) = self.pending.as_mut() {
+            let mergeable =
+                now < pending.first_at + MAX_COALESCE_WAIT && can_merge(&pending.event, &event);
+            if mergeable {
+                merge(&mut pending.event, event);
+                pending.flush_at = now + window;
+                return None;
+            }
+        }
+
+        self.pending
+            .replace(Pending {
+                event,
+                first_at: now,
+                flush_at: now + window,
+            })
+            .map(|pending| pending.event)
+    }
+
+    /// 統合待ちのイベントを保存する時刻
+    pub fn flush_at(&self) -> Option<Instant> {
+        self.pending.as_ref().map(|pending| pending.flush_at)
+    }
+
+    /// 統合待ちのイベントを取り出す
+    pub fn take(&mut self) -> Option<CaptureEvent> {
+        self.pending.take().map(|pending| pending.event)
+    }
+}
+
+/// 同じ取得元・同じコピー元アプリからの変更か（コピー元が不明な場合は統合する）
+fn can_merge(pending: &CaptureEvent, event: &CaptureEvent) -> bool {
+    if pending.concealed || event.concealed || pending.item.selection != event.item.selection {
+        return false;
+    }
+
+    match (&pending.item.source.name, &event.item.source.name) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+/// 後から届いた形式で上書きしながら全形式を統合
+fn merge(pending: &mut CaptureEvent, event: CaptureEvent) {
+    let item = &mut pending.item;
+    let new = event.item;
+
+    // 同じ形式がテキストとバイナリの両方に残らないようにする
+    for format in new.format_contents.keys() {
+        item.binary_contents.remove(format);
+    }
+    for format in new.binary_contents.keys() {
+        item.format_contents.remove(format);
+    }
+
+    if format_rank(&new.primary_format) <= format_rank(&item.primary_format) {
+        item.primary_format = new.primary_format;
+    }
+    if new.source.name.is_some() {
+        item.source = new.source;
+    }
+    item.format_contents.extend(new.format_contents);
+    item.binary_contents.extend(new.binary_contents);
+    item.images.extend(new.images);
+}
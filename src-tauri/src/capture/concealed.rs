@@ -0,0 +1,40 @@
+use clipboard_rs::{Clipboard, ClipboardContext};
+
+/// パスワードマネージャーが機密データのコピー時に付与するクリップボード形式
+const CONCEALED_HINTS: &[&str] = &[
+    "x-kde-passwordManagerHint", // KDE Klipper互換（KeePassXCなど）
+    "application/x-nspasteboard-concealed-type", // nspasteboard.org（Linux上の表記）
+    "org.nspasteboard.ConcealedType", // macOS
+    "ExcludeClipboardContentFromMonitorProcessing", // Windows
+];
+
+/// KDEのヒントで機密データを示す値
+const KDE_SECRET_VALUE: &str = "secret";
+
+/// 提示された形式に機密ヒントが含まれていれば、そのヒント名を返す
+///
+/// コンテンツ本体は読み取らず、形式名（とKDEのヒント値）だけで判定する。
+pub fn find_hint(ctx: &ClipboardContext, targets: &[String]) -> Option<&'static str> {
+    find_hint_with(targets, |hint| ctx.get_buffer(hint).ok())
+}
+
+/// 形式の値の読み取り方を指定して機密ヒントを探す（PRIMARYセレクションなどClipboardContext以外用）
+pub fn find_hint_with(
+    targets: &[String],
+    mut read_value: impl FnMut(&str) -> Option<Vec<u8>>,
+) -> Option<&'static str> {
+    CONCEALED_HINTS.iter().copied().find(|hint| {
+        if !targets.iter().any(|target| target == hint) {
+            return false;
+        }
+
+        // KDEのヒントは値が "secret" の場合のみ機密扱い（読み取れない場合は安全側に倒す）
+        if *hint == "x-kde-passwordManagerHint" {
+            if let Some(value) = read_value(hint) {
+                return String::from_utf8_lossy(&value).trim() == KDE_SECRET_VALUE;
+            }
+        }
+
+        true
+    })
+}
@@ -0,0 +1,227 @@
+use crate::capture::classifier::ContentKind;
+use crate::database::{FileEntry, NewClipboardItem};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 1アイテムあたりに解決するファイル数の上限
+const MAX_FILE_ENTRIES: usize = 1000;
+
+/// 形式判定のために読み込む先頭バイト数
+const SNIFF_BYTES: usize = 512;
+
+/// 先頭バイト列のシグネチャとMIMEタイプ
+const MAGIC_SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"BM", "image/bmp"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"Rar!\x1a\x07", "application/vnd.rar"),
+    (b"ID3", "audio/mpeg"),
+    (b"fLaC", "audio/flac"),
+    (b"OggS", "audio/ogg"),
+    (b"\x1a\x45\xdf\xa3", "video/x-matroska"),
+    (b"\x7fELF", "application/x-executable"),
+    (b"MZ", "application/x-msdownload"),
+    (b"SQLite format 3\0", "application/vnd.sqlite3"),
+];
+
+/// 拡張子とMIMEタイプ（シグネチャで判定できない形式・ZIPコンテナの判別用）
+const EXTENSION_TYPES: &[(&str, &str)] = &[
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("csv", "text/csv"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("css", "text/css"),
+    ("js", "text/javascript"),
+    ("ts", "text/typescript"),
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("svg", "image/svg+xml"),
+    ("rtf", "text/rtf"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    (
+        "pptx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+    ("odt", "application/vnd.oasis.opendocument.text"),
+    ("ods", "application/vnd.oasis.opendocument.spreadsheet"),
+    ("mp3", "audio/mpeg"),
+    ("wav", "audio/wav"),
+    ("mp4", "video/mp4"),
+    ("mov", "video/quicktime"),
+    ("webm", "video/webm"),
+];
+
+/// ファイル・ファイルリストのアイテムから各ファイルの情報を解決
+///
+/// ファイルリスト形式の各行、またはファイルパスと判定されたテキストが対象。
+pub fn resolve_item(item: &NewClipboardItem) -> Vec<FileEntry> {
+    if let Some(list) = item.format_contents.get("application/x-file-list") {
+        return resolve_all(list.lines());
+    }
+
+    let is_path = item
+        .classification
+        .as_ref()
+        .is_some_and(|c| c.kind == ContentKind::FilePath);
+    match item.format_contents.get(&item.primary_format) {
+        Some(text) if is_path => vec![resolve(text)],
+        _ => Vec::new(),
+    }
+}
+
+/// 複数のパスをまとめて解決（空行は除き、上限までの件数）
+pub fn resolve_all<'a>(paths: impl IntoIterator<Item = &'a str>) -> Vec<FileEntry> {
+    paths
+        .into_iter()
+        .filter(|path| !path.trim().is_empty())
+        .take(MAX_FILE_ENTRIES)
+        .map(resolve)
+        .collect()
+}
+
+/// パス（file:// URIも可）を絶対パスに解決し、存在・サイズ・更新日時・種類を調べる
+pub fn resolve(path: &str) -> FileEntry {
+    let path = absolute_path(path.trim());
+    let checked_at = chrono::Utc::now().timestamp_millis();
+
+    let Ok(metadata) = std::fs::metadata(&path) else {
+        return FileEntry {
+            path: path.to_string_lossy().into_owned(),
+            exists: false,
+            is_dir: false,
+            size: None,
+            modified_at: None,
+            mime_type: None,
+            checked_at,
+        };
+    };
+
+    // シンボリックリンクなどは実体のパスにそろえる
+    let path = path.canonicalize().unwrap_or(path);
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp_millis());
+    let mime_type = if metadata.is_dir() {
+        Some("inode/directory".to_string())
+    } else {
+        sniff_mime_type(&path)
+    };
+
+    FileEntry {
+        path: path.to_string_lossy().into_owned(),
+        exists: true,
+        is_dir: metadata.is_dir(),
+        size: metadata.is_file().then_some(metadata.len() as i64),
+        modified_at,
+        mime_type,
+        checked_at,
+    }
+}
+
+/// file:// URI・ホームディレクトリ（~/）を展開して絶対パスにする
+fn absolute_path(path: &str) -> PathBuf {
+    let path = match path.strip_prefix("file://") {
+        // file://host/path のホスト部分は無視する
+        Some(rest) => {
+            let rest = rest.find('/').map_or(rest, |slash| &rest[slash..]);
+            PathBuf::from(percent_decode(rest))
+        }
+        None => match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => PathBuf::from(path),
+        },
+    };
+
+    std::path::absolute(&path).unwrap_or(path)
+}
+
+/// URIのパーセントエンコーディングをデコード（不正な並びはそのまま残す）
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// ファイルの種類を先頭バイト列と拡張子から判定
+fn sniff_mime_type(path: &Path) -> Option<String> {
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    if let Ok(file) = File::open(path) {
+        let _ = file.take(SNIFF_BYTES as u64).read_to_end(&mut head);
+    }
+
+    let by_extension = path.extension().and_then(|ext| {
+        let ext = ext.to_string_lossy().to_ascii_lowercase();
+        EXTENSION_TYPES
+            .iter()
+            .find(|(known, _)| *known == ext)
+            .map(|(_, mime)| mime.to_string())
+    });
+
+    if let Some((_, mime)) = MAGIC_SIGNATURES
+        .iter()
+        .find(|(signature, _)| head.starts_with(signature))
+    {
+        // Office文書などのZIPコンテナは拡張子で種類を区別する
+        if *mime == "application/zip" && by_extension.is_some() {
+            return by_extension;
+        }
+        return Some(mime.to_string());
+    }
+
+    // RIFFコンテナ・ISOメディアは種類の識別子がオフセット位置にある
+    match (head.get(8..12), head.get(4..8)) {
+        (Some(b"WEBP"), _) => return Some("image/webp".to_string()),
+        (Some(b"WAVE"), _) => return Some("audio/wav".to_string()),
+        (_, Some(b"ftyp")) => return Some("video/mp4".to_string()),
+        _ => {}
+    }
+
+    if by_extension.is_some() {
+        return by_extension;
+    }
+
+    // 不明な形式でも先頭がUTF-8テキストならテキストとして扱う（末尾で文字が途切れていてもよい）
+    let is_utf8 = match std::str::from_utf8(&head) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    let is_text = !head.is_empty() && !head.contains(&0) && is_utf8;
+    is_text.then(|| "text/plain".to_string())
+}
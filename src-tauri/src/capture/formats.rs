@@ -0,0 +1,270 @@
+use super::markup;
+use crate::database::ClipboardItem;
+use clipboard_rs::common::{RustImage, RustImageData};
+use clipboard_rs::{Clipboard, ClipboardContent as WriteContent, ClipboardContext, ContentFormat};
+use std::collections::HashMap;
+use tracing::error;
+
+/// 組み込みの形式（clipboard-rsのContentFormatで取得・設定する）
+const BUILTIN_FORMATS: &[&str] = &[
+    "text/plain",
+    "text/html",
+    "text/rtf",
+    "image/png",
+    "application/x-file-list",
+];
+
+/// 追加で保存する形式の既定値
+pub fn default_extra_formats() -> Vec<String> {
+    [
+        "image/svg+xml",                     // Inkscapeなど
+        "text/x-moz-url",                    // Firefox
+        "application/vnd.code.copymetadata", // VS Code
+        "application/x-openoffice-*",        // LibreOffice
+    ]
+    .iter()
+    .map(|format| format.to_string())
+    .collect()
+}
+
+/// 形式名がパターンに一致するか（末尾の * は前方一致、大文字小文字を区別しない）
+pub fn matches_pattern(pattern: &str, format: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => format
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix)),
+        None => format.eq_ignore_ascii_case(pattern),
+    }
+}
+
+/// 提示された形式のうち設定された追加形式を生のバイト列として取得
+///
+/// 組み込みの形式として取得済みのものは除く。
+pub fn collect_extra_formats(
+    ctx: &ClipboardContext,
+    targets: &[String],
+    patterns: &[String],
+    captured: impl Fn(&str) -> bool,
+) -> HashMap<String, Vec<u8>> {
+    let mut contents = HashMap::new();
+
+    for target in targets {
+        if captured(target) || !patterns.iter().any(|p| matches_pattern(p, target)) {
+            continue;
+        }
+
+        match ctx.get_buffer(target) {
+            Ok(bytes) if bytes.is_empty() => {}
+            Ok(bytes) => {
+                contents.insert(target.clone(), bytes);
+            }
+            Err(e) => error!("追加形式取得エラー ({}): {}", target, e),
+        }
+    }
+
+    contents
+}
+
+/// 保存済みアイテムの全形式をクリップボードに書き戻す内容に変換
+///
+/// テキストの判定結果（text/uri-listなど）はテキストとして、追加形式は元の形式名で書き戻す。
+/// テキストとして書き戻すのは1つだけで、text/plain・元のアプリが提供した形式（プライマリ優先）・
+/// 派生させた形式（Markdownなど）の順に選ぶ。
+pub fn to_clipboard_contents(item: &ClipboardItem) -> Vec<WriteContent> {
+    let mut contents = Vec::new();
+    let mut has_text = false;
+
+    let mut sorted: Vec<_> = item.contents.iter().collect();
+    sorted.sort_by_key(|content| {
+        (
+            content.format != "text/plain",
+            content.is_derived,
+            content.format != item.primary_format,
+        )
+    });
+
+    for content in sorted {
+        let format = content.format.as_str();
+        match (&content.content_blob, format) {
+            (Some(bytes), "image/png") => match RustImageData::from_bytes(bytes) {
+                Ok(image) => contents.push(WriteContent::Image(image)),
+                Err(e) => error!("画像の復元エラー: {}", e),
+            },
+            (Some(bytes), _) => {
+                contents.push(WriteContent::Other(format.to_string(), bytes.clone()))
+            }
+            (None, "text/html") => contents.push(WriteContent::Html(content.content.clone())),
+            (None, "text/rtf") => contents.push(WriteContent::Rtf(content.content.clone())),
+            (None, "application/x-file-list") => contents.push(WriteContent::Files(
+                content.content.lines().map(str::to_string).collect(),
+            )),
+            (None, _) if !has_text => {
+                has_text = true;
+                contents.push(WriteContent::Text(content.content.clone()));
+            }
+            (None, _) => {}
+        }
+    }
+
+    contents
+}
+
+/// 組み込みの形式か
+pub fn is_builtin(format: &str) -> bool {
+    BUILTIN_FORMATS.contains(&format)
+}
+
+/// 組み込みの形式の取得に使うclipboard-rsの形式
+fn content_format(format: &str) -> Option<ContentFormat> {
+    match format {
+        "text/plain" => Some(ContentFormat::Text),
+        "text/html" => Some(ContentFormat::Html),
+        "text/rtf" => Some(ContentFormat::Rtf),
+        "image/png" => Some(ContentFormat::Image),
+        "application/x-file-list" => Some(ContentFormat::Files),
+        _ => None,
+    }
+}
+
+/// クリップボードの内容を記録した場合に保存される形式
+///
+/// 提示されている組み込みの形式・設定された追加形式と、それらから派生させる形式を返す。
+pub fn available(
+    ctx: &ClipboardContext,
+    targets: &[String],
+    extra_patterns: &[String],
+    derive_markdown: bool,
+) -> Vec<String> {
+    let mut formats: Vec<String> = BUILTIN_FORMATS
+        .iter()
+        .filter(|format| content_format(format).is_some_and(|content| ctx.has(content)))
+        .map(|format| format.to_string())
+        .collect();
+
+    for target in targets {
+        if !is_builtin(target)
+            && !formats.contains(target)
+            && extra_patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern, target))
+        {
+            formats.push(target.clone());
+        }
+    }
+
+    let derived = markup::derivable_formats(&formats, derive_markdown);
+    formats.extend(derived.into_iter().map(str::to_string));
+    formats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::ClipboardContent;
+    use chrono::Utc;
+
+    fn content(format: &str, text: &str, is_derived: bool) -> ClipboardContent {
+        ClipboardContent {
+            item_id: "item".to_string(),
+            format: format.to_string(),
+            content: text.to_string(),
+            content_blob: None,
+            blob_ref: None,
+            blob_kind: None,
+            data_size: text.len() as i64,
+            content_hash: None,
+            image_metadata: None,
+            is_derived,
+            is_truncated: false,
+            original_size: None,
+            is_partial: false,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn item(primary_format: &str, contents: Vec<ClipboardContent>) -> ClipboardItem {
+        ClipboardItem {
+            id: "item".to_string(),
+            primary_format: primary_format.to_string(),
+            timestamp: 0,
+            is_favorite: false,
+            source_app: None,
+            source_executable: None,
+            source_window_title: None,
+            created_at: Utc::now(),
+            content_hash: None,
+            copy_count: 1,
+            last_used_at: None,
+            use_count: 0,
+            expires_at: None,
+            is_sensitive: false,
+            sensitive_kind: None,
+            selection: "clipboard".to_string(),
+            kind: None,
+            kind_confidence: None,
+            kind_detail: None,
+            contents,
+            files: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn texts(contents: &[WriteContent]) -> Vec<&str> {
+        contents
+            .iter()
+            .filter_map(|content| match content {
+                WriteContent::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plain_text_is_written_instead_of_derived_markdown() {
+        let item = item(
+            "text/html",
+            vec![
+                content("text/html", "<b>bold</b>", false),
+                content("text/markdown", "**bold**", true),
+                content("text/plain", "bold", false),
+            ],
+        );
+
+        let contents = to_clipboard_contents(&item);
+
+        assert_eq!(texts(&contents), vec!["bold"]);
+        assert!(contents
+            .iter()
+            .any(|content| matches!(content, WriteContent::Html(html) if html == "<b>bold</b>")));
+    }
+
+    #[test]
+    fn derived_plain_text_is_preferred_over_derived_markdown() {
+        let item = item(
+            "text/html",
+            vec![
+                content("text/html", "<b>bold</b>", false),
+                content("text/markdown", "**bold**", true),
+                content("text/plain", "bold", true),
+            ],
+        );
+
+        assert_eq!(texts(&to_clipboard_contents(&item)), vec!["bold"]);
+    }
+
+    #[test]
+    fn provided_text_format_is_preferred_over_derived() {
+        let item = item(
+            "text/uri-list",
+            vec![
+                content("text/markdown", "[a](https://example.com)", true),
+                content("text/uri-list", "https://example.com", false),
+            ],
+        );
+
+        assert_eq!(
+            texts(&to_clipboard_contents(&item)),
+            vec!["https://example.com"]
+        );
+    }
+}
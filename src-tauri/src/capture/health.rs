@@ -0,0 +1,55 @@
+use serde::Serialize;
+use std::sync::Mutex as StdMutex;
+
+/// ウォッチャーの稼働状況
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WatcherHealth {
+    pub restarting: bool,             // ウォッチャーが終了し、再起動を待っている
+    pub restart_count: u32,           // 起動してからウォッチャーを再起動した回数
+    pub last_capture_at: Option<i64>, // 最後にアイテムを記録した時刻（ミリ秒）
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>, // 最後のエラーの発生時刻（ミリ秒）
+}
+
+// 稼働状況（ウォッチャーのスレッドとキャプチャワーカーから更新）
+static HEALTH: StdMutex<WatcherHealth> = StdMutex::new(WatcherHealth {
+    restarting: false,
+    restart_count: 0,
+    last_capture_at: None,
+    last_error: None,
+    last_error_at: None,
+});
+
+/// 現在の稼働状況
+pub fn snapshot() -> WatcherHealth {
+    HEALTH.lock().unwrap().clone()
+}
+
+/// アイテムの記録に成功したことを記録
+pub fn record_capture() {
+    HEALTH.lock().unwrap().last_capture_at = Some(chrono::Utc::now().timestamp_millis());
+}
+
+/// エラーを記録
+pub fn record_error(message: impl Into<String>) {
+    let mut health = HEALTH.lock().unwrap();
+    health.last_error = Some(message.into());
+    health.last_error_at = Some(chrono::Utc::now().timestamp_millis());
+}
+
+/// ウォッチャーの終了を記録し、再起動待ちにする
+pub fn mark_restarting() {
+    HEALTH.lock().unwrap().restarting = true;
+}
+
+/// ウォッチャーの再起動を記録
+pub fn record_restart() {
+    let mut health = HEALTH.lock().unwrap();
+    health.restarting = false;
+    health.restart_count += 1;
+}
+
+/// 再起動待ちを解除（監視の停止時）
+pub fn clear_restarting() {
+    HEALTH.lock().unwrap().restarting = false;
+}
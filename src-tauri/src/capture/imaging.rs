@@ -0,0 +1,67 @@
+use crate::database::{hash, CapturedImage, ImageMetadata};
+use clipboard_rs::common::{RustImage, RustImageData};
+use image::{DynamicImage, ImageFormat};
+use std::io::Cursor;
+
+/// サムネイルの最大辺（ピクセル）
+const THUMBNAIL_MAX_SIZE: u32 = 256;
+
+/// クリップボードの画像をメモリ上でPNGにエンコード
+pub fn encode_png(image: &RustImageData) -> Result<Vec<u8>, String> {
+    image
+        .to_png()
+        .map(|buffer| buffer.get_bytes().to_vec())
+        .map_err(|e| format!("PNGエンコードエラー: {}", e))
+}
+
+/// クリップボードの画像からメタデータとサムネイルを生成
+pub fn capture_image(image: &RustImageData, png: &[u8]) -> Result<CapturedImage, String> {
+    let dynamic = image
+        .get_dynamic_image()
+        .map_err(|e| format!("画像デコードエラー: {}", e))?;
+
+    describe_image(&dynamic, png.len())
+}
+
+/// エンコード済み画像（既存データ）からメタデータとサムネイルを生成
+pub fn analyze_encoded(bytes: &[u8]) -> Result<CapturedImage, String> {
+    let dynamic =
+        image::load_from_memory(bytes).map_err(|e| format!("画像デコードエラー: {}", e))?;
+
+    describe_image(&dynamic, bytes.len())
+}
+
+/// デコードしたピクセルのハッシュ（再エンコードでバイト列が変わっても同じ画像なら一致）
+pub fn pixel_hash(bytes: &[u8]) -> Result<String, String> {
+    let rgba = image::load_from_memory(bytes)
+        .map_err(|e| format!("画像デコードエラー: {}", e))?
+        .to_rgba8();
+
+    let mut data = Vec::with_capacity(8 + rgba.as_raw().len());
+    data.extend_from_slice(&rgba.width().to_le_bytes());
+    data.extend_from_slice(&rgba.height().to_le_bytes());
+    data.extend_from_slice(rgba.as_raw());
+    Ok(hash::binary_hash(&data))
+}
+
+/// 画像の寸法・ピクセル形式を記録し、縮小したPNGサムネイルを作成
+fn describe_image(image: &DynamicImage, byte_size: usize) -> Result<CapturedImage, String> {
+    let thumbnail = image.thumbnail(THUMBNAIL_MAX_SIZE, THUMBNAIL_MAX_SIZE);
+
+    let mut thumbnail_png = Vec::new();
+    thumbnail
+        .write_to(&mut Cursor::new(&mut thumbnail_png), ImageFormat::Png)
+        .map_err(|e| format!("サムネイルエンコードエラー: {}", e))?;
+
+    Ok(CapturedImage {
+        metadata: ImageMetadata {
+            width: image.width(),
+            height: image.height(),
+            pixel_format: format!("{:?}", image.color()),
+            byte_size: byte_size as i64,
+            thumbnail_width: Some(thumbnail.width()),
+            thumbnail_height: Some(thumbnail.height()),
+        },
+        thumbnail: thumbnail_png,
+    })
+}
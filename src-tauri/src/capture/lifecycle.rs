@@ -0,0 +1,70 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex as StdMutex;
+use tracing::{error, warn};
+
+/// 再起動後も引き継ぐ監視状態
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifecycleState {
+    pub stopped: bool,             // ユーザーが監視を停止したまま終了した
+    pub paused_until: Option<i64>, // 期限付きの一時停止の再開予定時刻（ミリ秒）
+}
+
+// 読み込みから書き込みまでの間に他の更新が割り込まないようにする
+static LOCK: StdMutex<()> = StdMutex::new(());
+
+/// 状態ファイルのパス（設定ファイルと同じdataディレクトリ内）
+fn state_path() -> Result<PathBuf> {
+    Ok(std::env::current_dir()?
+        .join("data")
+        .join("monitoring_state.json"))
+}
+
+fn read() -> Result<LifecycleState> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(LifecycleState::default());
+    }
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn write(state: &LifecycleState) -> Result<()> {
+    let path = state_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// 前回終了時の監視状態を読み込む（読み込めない場合は既定の状態）
+pub fn load() -> LifecycleState {
+    let _guard = LOCK.lock().unwrap();
+    read().unwrap_or_else(|e| {
+        warn!("監視状態の読み込みに失敗: {}", e);
+        LifecycleState::default()
+    })
+}
+
+/// 状態の一部を更新して保存
+fn update(apply: impl FnOnce(&mut LifecycleState)) {
+    let _guard = LOCK.lock().unwrap();
+    let mut state = read().unwrap_or_default();
+    apply(&mut state);
+    if let Err(e) = write(&state) {
+        error!("監視状態の保存に失敗: {}", e);
+    }
+}
+
+/// ユーザーによる監視の開始・停止を記録
+pub fn set_stopped(stopped: bool) {
+    update(|state| state.stopped = stopped);
+}
+
+/// 一時停止の再開予定時刻を記録（再開した場合・再起動までの一時停止はNone）
+pub fn set_paused_until(until: Option<i64>) {
+    update(|state| state.paused_until = until);
+}
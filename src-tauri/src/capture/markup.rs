@@ -0,0 +1,816 @@
+use crate::database::NewClipboardItem;
+
+/// HTML・RTFから派生形式を生成して追加（元のアプリがテキストを提供しなかった場合のみ）
+///
+/// 派生させた形式はderived_formatsに記録され、検索・プレビューに使われる。
+pub fn add_derived_formats(item: &mut NewClipboardItem, markdown: bool) {
+    let html = item.format_contents.get("text/html").cloned();
+    let rtf = item.format_contents.get("text/rtf").cloned();
+
+    if !item.format_contents.contains_key("text/plain") {
+        let text = match (&html, &rtf) {
+            (Some(html), _) => Some(html_to_text(html)),
+            (None, Some(rtf)) => Some(rtf_to_text(rtf)),
+            (None, None) => None,
+        };
+        if let Some(text) = text.filter(|text| !text.is_empty()) {
+            insert_derived(item, "text/plain", text);
+        }
+    }
+
+    if markdown && !item.format_contents.contains_key("text/markdown") {
+        if let Some(markdown) = html.map(|html| html_to_markdown(&html)) {
+            if !markdown.is_empty() {
+                insert_derived(item, "text/markdown", markdown);
+            }
+        }
+    }
+}
+
+/// 提示された形式からadd_derived_formatsで派生させる形式（内容によっては派生しない場合もある）
+pub fn derivable_formats(formats: &[String], markdown: bool) -> Vec<&'static str> {
+    let has = |format: &str| formats.iter().any(|f| f == format);
+    let mut derived = Vec::new();
+
+    if !has("text/plain") && (has("text/html") || has("text/rtf")) {
+        derived.push("text/plain");
+    }
+    if markdown && !has("text/markdown") && has("text/html") {
+        derived.push("text/markdown");
+    }
+
+    derived
+}
+
+fn insert_derived(item: &mut NewClipboardItem, format: &str, content: String) {
+    item.format_contents.insert(format.to_string(), content);
+    item.derived_formats.insert(format.to_string());
+}
+
+/// HTMLをプレーンテキストに変換
+pub fn html_to_text(html: &str) -> String {
+    convert_html(html, false)
+}
+
+/// HTMLをMarkdownに変換（見出し・リスト・リンク・強調・コードなど）
+pub fn html_to_markdown(html: &str) -> String {
+    convert_html(html, true)
+}
+
+/// 内容を出力しない要素
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "head", "title", "noscript", "template", "svg",
+];
+
+/// 前後に空行を入れるブロック要素
+const PARAGRAPH_ELEMENTS: &[&str] = &[
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "pre",
+    "blockquote",
+    "table",
+    "hr",
+    "figure",
+];
+
+/// 前後で改行するブロック要素
+const BLOCK_ELEMENTS: &[&str] = &[
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "nav",
+    "aside",
+    "main",
+    "tr",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "ul",
+    "ol",
+    "form",
+    "figcaption",
+    "caption",
+    "address",
+];
+
+/// 開始・終了タグ
+struct Tag<'a> {
+    name: String,
+    closing: bool,
+    attributes: &'a str,
+}
+
+impl Tag<'_> {
+    /// 属性値を取得（引用符の有無どちらにも対応）
+    fn attribute(&self, name: &str) -> Option<String> {
+        let lower = self.attributes.to_ascii_lowercase();
+        let mut search = 0;
+        while let Some(found) = lower[search..].find(name) {
+            let start = search + found;
+            search = start + name.len();
+
+            // 属性名の途中に一致した場合は読み飛ばす
+            let preceded = lower[..start]
+                .chars()
+                .next_back()
+                .is_none_or(char::is_whitespace);
+            let rest = self.attributes[search..].trim_start();
+            if !preceded || !rest.starts_with('=') {
+                continue;
+            }
+
+            let value = rest[1..].trim_start();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+                _ => value.split(char::is_whitespace).next().unwrap_or_default(),
+            };
+            return Some(decode_entities(value));
+        }
+        None
+    }
+}
+
+/// 表の変換状態（Markdownの区切り行の出力用）
+#[derive(Default)]
+struct TableState {
+    rows: usize,
+    cells: usize,
+}
+
+/// 変換結果を組み立てる
+struct Writer {
+    out: String,
+    markdown: bool,
+    pending_space: bool,
+    pre_depth: usize,
+    lists: Vec<Option<u32>>, // 入れ子のリスト（番号付きの場合は次の番号）
+    links: Vec<Option<String>>,
+    tables: Vec<TableState>,
+}
+
+impl Writer {
+    fn new(markdown: bool) -> Self {
+        Self {
+            out: String::new(),
+            markdown,
+            pending_space: false,
+            pre_depth: 0,
+            lists: Vec::new(),
+            links: Vec::new(),
+            tables: Vec::new(),
+        }
+    }
+
+    /// テキストを出力（pre要素の外では空白をまとめる）
+    fn text(&mut self, text: &str) {
+        if self.pre_depth > 0 {
+            self.out.push_str(text);
+            return;
+        }
+
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.pending_space = true;
+                continue;
+            }
+            if self.pending_space && !self.out.is_empty() && !self.out.ends_with([' ', '\n']) {
+                self.out.push(' ');
+            }
+            self.pending_space = false;
+            self.out.push(c);
+        }
+    }
+
+    /// 記号をそのまま出力（直前の空白は保つ）
+    fn raw(&mut self, text: &str) {
+        if self.pending_space && !self.out.is_empty() && !self.out.ends_with([' ', '\n']) {
+            self.out.push(' ');
+        }
+        self.pending_space = false;
+        self.out.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+        self.pending_space = false;
+    }
+
+    fn blank_line(&mut self) {
+        self.newline();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn open(&mut self, tag: &Tag) {
+        let name = tag.name.as_str();
+        match name {
+            "br" => {
+                self.out.push('\n');
+                self.pending_space = false;
+            }
+            "pre" => {
+                self.blank_line();
+                if self.markdown {
+                    self.out.push_str("```\n");
+                }
+                self.pre_depth += 1;
+            }
+            "hr" => {
+                self.blank_line();
+                if self.markdown {
+                    self.out.push_str("---");
+                }
+                self.blank_line();
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.blank_line();
+                if self.markdown {
+                    let level = name[1..].parse().unwrap_or(1);
+                    self.out.push_str(&"#".repeat(level));
+                    self.out.push(' ');
+                }
+            }
+            "blockquote" => {
+                self.blank_line();
+                if self.markdown {
+                    self.out.push_str("> ");
+                }
+            }
+            "ul" | "ol" => {
+                self.newline();
+                self.lists.push((name == "ol").then_some(1));
+            }
+            "li" => {
+                self.newline();
+                let depth = self.lists.len().saturating_sub(1);
+                self.out.push_str(&"  ".repeat(depth));
+                match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        self.out.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => self.out.push_str("- "),
+                }
+            }
+            "table" => {
+                self.blank_line();
+                self.tables.push(TableState::default());
+            }
+            "tr" => {
+                self.newline();
+                if let Some(table) = self.tables.last_mut() {
+                    table.cells = 0;
+                }
+            }
+            "td" | "th" => {
+                let markdown = self.markdown;
+                let Some(table) = self.tables.last_mut() else {
+                    return;
+                };
+                let separator = match (markdown, table.cells) {
+                    (true, 0) => "| ",
+                    (true, _) => " | ",
+                    (false, 0) => "",
+                    (false, _) => "\t",
+                };
+                table.cells += 1;
+                self.out.push_str(separator);
+                self.pending_space = false;
+            }
+            "a" if self.markdown => {
+                let href = tag.attribute("href").filter(|href| !href.starts_with('#'));
+                if href.is_some() {
+                    self.raw("[");
+                }
+                self.links.push(href);
+            }
+            "img" => {
+                let alt = tag.attribute("alt").unwrap_or_default();
+                match tag.attribute("src").filter(|_| self.markdown) {
+                    // data URLはMarkdownに埋め込まない
+                    Some(src) if !src.starts_with("data:") => {
+                        self.raw(&format!("![{}]({})", alt, src))
+                    }
+                    _ => self.text(&alt),
+                }
+            }
+            "strong" | "b" if self.markdown => self.raw("**"),
+            "em" | "i" if self.markdown => self.raw("*"),
+            "del" | "s" | "strike" if self.markdown => self.raw("~~"),
+            "code" if self.markdown && self.pre_depth == 0 => self.raw("`"),
+            _ if PARAGRAPH_ELEMENTS.contains(&name) => self.blank_line(),
+            _ if BLOCK_ELEMENTS.contains(&name) => self.newline(),
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "pre" => {
+                self.pre_depth = self.pre_depth.saturating_sub(1);
+                if self.markdown {
+                    self.newline();
+                    self.out.push_str("```");
+                }
+                self.blank_line();
+            }
+            "ul" | "ol" => {
+                self.lists.pop();
+                self.newline();
+            }
+            "table" => {
+                self.tables.pop();
+                self.blank_line();
+            }
+            "tr" => {
+                let markdown = self.markdown;
+                if let Some(table) = self.tables.last_mut() {
+                    table.rows += 1;
+                    let cells = table.cells;
+                    // Markdownの表は1行目を見出しとして区切り行を入れる
+                    if markdown && cells > 0 {
+                        self.out.push_str(" |");
+                        if table.rows == 1 {
+                            self.out.push('\n');
+                            self.out.push_str(&format!("|{}", " --- |".repeat(cells)));
+                        }
+                    }
+                }
+                self.newline();
+            }
+            "a" if self.markdown => {
+                if let Some(Some(href)) = self.links.pop() {
+                    self.out.push_str(&format!("]({})", href));
+                }
+            }
+            "strong" | "b" if self.markdown => self.out.push_str("**"),
+            "em" | "i" if self.markdown => self.out.push('*'),
+            "del" | "s" | "strike" if self.markdown => self.out.push_str("~~"),
+            "code" if self.markdown && self.pre_depth == 0 => self.out.push('`'),
+            _ if PARAGRAPH_ELEMENTS.contains(&name) => self.blank_line(),
+            _ if BLOCK_ELEMENTS.contains(&name) => self.newline(),
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> String {
+        normalize_lines(&self.out)
+    }
+}
+
+fn convert_html(html: &str, markdown: bool) -> String {
+    let mut writer = Writer::new(markdown);
+    let mut rest = html;
+    let mut skipping: Option<String> = None; // 内容を出力しない要素の中
+
+    while !rest.is_empty() {
+        // コメント・CDATA・DOCTYPE
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+
+        if rest.starts_with('<') {
+            match parse_tag(rest) {
+                Some((tag, length)) => {
+                    rest = &rest[length..];
+                    match &skipping {
+                        Some(name) if tag.closing && tag.name == *name => skipping = None,
+                        Some(_) => {}
+                        None if !tag.closing && SKIPPED_ELEMENTS.contains(&tag.name.as_str()) => {
+                            skipping = Some(tag.name)
+                        }
+                        None if tag.closing => writer.close(&tag.name),
+                        None => writer.open(&tag),
+                    }
+                }
+                // タグとして解釈できない「<」は文字として扱う
+                None => {
+                    if skipping.is_none() {
+                        writer.text("<");
+                    }
+                    rest = &rest[1..];
+                }
+            }
+            continue;
+        }
+
+        let end = rest.find('<').unwrap_or(rest.len());
+        if skipping.is_none() {
+            writer.text(&decode_entities(&rest[..end]));
+        }
+        rest = &rest[end..];
+    }
+
+    writer.finish()
+}
+
+/// 「<」から始まるタグを解析し、タグと長さを返す
+fn parse_tag(text: &str) -> Option<(Tag<'_>, usize)> {
+    let body = &text[1..];
+    let closing = body.starts_with('/');
+    let body = if closing { &body[1..] } else { body };
+
+    let name_length = body
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != ':')
+        .unwrap_or(body.len());
+    if name_length == 0 || !body.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    // 引用符内の「>」は無視してタグの終わりを探す
+    let mut quote = None;
+    let end = body.char_indices().find_map(|(i, c)| {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+        None
+    })?;
+
+    let tag = Tag {
+        name: body[..name_length].to_ascii_lowercase(),
+        closing,
+        attributes: body[name_length..end].trim_end_matches('/'),
+    };
+    let length = text.len() - body.len() + end + 1;
+    Some((tag, length))
+}
+
+/// HTMLの文字参照をデコード
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..=end]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, length)) => {
+                out.push(c);
+                rest = &rest[length..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(number) = entity.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+
+    let c = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "bull" => '•',
+        "middot" => '·',
+        "yen" => '¥',
+        "euro" => '€',
+        "times" => '×',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// RTFの出力しないグループ（フォント表・スタイル・埋め込み画像など）
+const RTF_SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "object",
+    "header",
+    "footer",
+    "headerl",
+    "headerr",
+    "footerl",
+    "footerr",
+    "listtable",
+    "listoverridetable",
+    "rsidtbl",
+    "generator",
+    "xmlnstbl",
+    "themedata",
+    "colorschememapping",
+    "latentstyles",
+    "datastore",
+    "fldinst",
+    "filetbl",
+    "revtbl",
+];
+
+/// RTFのグループごとの状態
+#[derive(Clone, Copy)]
+struct RtfGroup {
+    skip: bool,
+    unicode_skip: usize, // \uN の後に読み飛ばす代替文字の数（\ucN）
+}
+
+/// RTFをプレーンテキストに変換
+///
+/// \'hh の8ビット文字はUTF-8として解釈できればUTF-8、できなければLatin-1として扱う。
+pub fn rtf_to_text(rtf: &str) -> String {
+    let bytes = rtf.as_bytes();
+    let mut out = String::new();
+    let mut pending_bytes: Vec<u8> = Vec::new();
+    let mut group = RtfGroup {
+        skip: false,
+        unicode_skip: 1,
+    };
+    let mut stack: Vec<RtfGroup> = Vec::new();
+    let mut fallback = 0; // 読み飛ばす残りの代替文字数
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let byte = bytes[i];
+
+        // \'hh 以外が来たら溜めた8ビット文字を出力
+        let is_hex_escape = byte == b'\\' && bytes.get(i + 1) == Some(&b'\'');
+        if !is_hex_escape && !pending_bytes.is_empty() {
+            flush_rtf_bytes(&mut out, &mut pending_bytes);
+        }
+
+        match byte {
+            b'{' => {
+                stack.push(group);
+                i += 1;
+            }
+            b'}' => {
+                group = stack.pop().unwrap_or(group);
+                i += 1;
+            }
+            b'\r' | b'\n' => i += 1,
+            b'\\' => {
+                let Some(&next) = bytes.get(i + 1) else {
+                    break;
+                };
+                if next == b'\'' {
+                    // 16進数2桁が続かない場合は \' だけを読み飛ばす（続く文字は通常の文字として扱う）
+                    let hex = bytes.get(i + 2..i + 4).unwrap_or_default();
+                    if hex.len() < 2 || !hex.iter().all(u8::is_ascii_hexdigit) {
+                        i += 2;
+                        continue;
+                    }
+                    let value = hex_value(hex[0]) << 4 | hex_value(hex[1]);
+                    if fallback > 0 {
+                        fallback -= 1;
+                    } else if !group.skip {
+                        pending_bytes.push(value);
+                    }
+                    i += 4;
+                    continue;
+                }
+
+                if !next.is_ascii_alphabetic() {
+                    // 制御記号（複数バイトの文字の場合は文字単位で読み飛ばす）
+                    let symbol = rtf[i + 1..].chars().next().unwrap_or_default();
+                    i += 1 + symbol.len_utf8();
+                    if group.skip {
+                        continue;
+                    }
+                    match next {
+                        b'\\' | b'{' | b'}' => out.push(next as char),
+                        b'~' => out.push(' '),
+                        b'_' => out.push('-'),
+                        b'\r' | b'\n' => out.push('\n'),
+                        b'*' => group.skip = true,
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 制御語（英字 + 省略可能な数値 + 区切りの空白1つ）
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && bytes[end].is_ascii_alphabetic() {
+                    end += 1;
+                }
+                let word = &rtf[start..end];
+                let number_start = end;
+                if end < bytes.len() && bytes[end] == b'-' {
+                    end += 1;
+                }
+                while end < bytes.len() && bytes[end].is_ascii_digit() {
+                    end += 1;
+                }
+                let parameter: Option<i32> = rtf[number_start..end].parse().ok();
+                if end < bytes.len() && bytes[end] == b' ' {
+                    end += 1;
+                }
+                i = end;
+
+                if RTF_SKIPPED_DESTINATIONS.contains(&word) {
+                    group.skip = true;
+                }
+                if group.skip {
+                    continue;
+                }
+
+                match word {
+                    "par" | "line" | "row" | "sect" | "page" => out.push('\n'),
+                    "tab" | "cell" => out.push('\t'),
+                    "emdash" => out.push('—'),
+                    "endash" => out.push('–'),
+                    "bullet" => out.push('•'),
+                    "lquote" => out.push('‘'),
+                    "rquote" => out.push('’'),
+                    "ldblquote" => out.push('“'),
+                    "rdblquote" => out.push('”'),
+                    "uc" => group.unicode_skip = parameter.unwrap_or(1).max(0) as usize,
+                    "u" => {
+                        // 負の値は16ビットの符号付きで表現されている
+                        let code = parameter.unwrap_or(0);
+                        let code = if code < 0 { code + 65536 } else { code };
+                        if let Some(c) = char::from_u32(code as u32) {
+                            out.push(c);
+                        }
+                        fallback = group.unicode_skip;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {
+                // 複数バイトの文字もそのまま出力する
+                let c = rtf[i..].chars().next().unwrap_or_default();
+                i += c.len_utf8();
+                if fallback > 0 {
+                    fallback -= 1;
+                } else if !group.skip {
+                    out.push(c);
+                }
+            }
+        }
+    }
+    flush_rtf_bytes(&mut out, &mut pending_bytes);
+
+    normalize_lines(&out)
+}
+
+/// 16進数字の値（is_ascii_hexdigitで確認済みのバイト）
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+fn flush_rtf_bytes(out: &mut String, bytes: &mut Vec<u8>) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => out.push_str(text),
+        Err(_) => out.extend(bytes.iter().map(|&b| b as char)),
+    }
+    bytes.clear();
+}
+
+/// 行末の空白を除き、3行以上の空行を1行にまとめる
+fn normalize_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_lines = 0;
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rtf_hex_escapes() {
+        assert_eq!(rtf_to_text(r"{\rtf1\ansi caf\'e9}"), "café");
+        // UTF-8として解釈できるバイト列
+        assert_eq!(rtf_to_text(r"{\rtf1 \'e3\'81\'82}"), "あ");
+    }
+
+    #[test]
+    fn rtf_hex_escape_followed_by_non_ascii() {
+        assert_eq!(rtf_to_text("{\\rtf1 \\'aé}"), "aé");
+    }
+
+    #[test]
+    fn rtf_non_ascii_control_symbol() {
+        assert_eq!(rtf_to_text("{\\rtf1 \\é after}"), "after");
+    }
+
+    #[test]
+    fn rtf_truncated_escapes() {
+        assert_eq!(rtf_to_text(r"{\rtf1 abc\'"), "abc");
+        assert_eq!(rtf_to_text(r"{\rtf1 abc\'4"), "abc4");
+        assert_eq!(rtf_to_text(r"{\rtf1 abc\"), "abc");
+    }
+
+    #[test]
+    fn rtf_unicode_and_skipped_groups() {
+        assert_eq!(rtf_to_text(r"{\rtf1 \u12354?x}"), "あx");
+        assert_eq!(
+            rtf_to_text(r"{\rtf1 {\fonttbl{\f0 Arial;}}Hello\par 日本語}"),
+            "Hello\n日本語"
+        );
+    }
+
+    #[test]
+    fn html_text_and_markdown() {
+        let html = "<p>Hello <b>world</b></p><p>Next &amp; more</p>";
+        assert_eq!(html_to_text(html), "Hello world\n\nNext & more");
+        assert_eq!(html_to_markdown(html), "Hello **world**\n\nNext & more");
+    }
+
+    #[test]
+    fn html_skips_scripts_and_keeps_headings() {
+        let html = "<h1>Title</h1><script>x()</script><p>body</p>";
+        assert_eq!(html_to_text(html), "Title\n\nbody");
+        assert_eq!(html_to_markdown(html), "# Title\n\nbody");
+    }
+
+    #[test]
+    fn html_lists_links_and_line_breaks() {
+        assert_eq!(
+            html_to_markdown("<ul><li>one</li><li>two</li></ul>"),
+            "- one\n- two"
+        );
+        let link = "<a href=\"https://example.com\">link</a>";
+        assert_eq!(html_to_text(link), "link");
+        assert_eq!(html_to_markdown(link), "[link](https://example.com)");
+        assert_eq!(html_to_text("a<br>b"), "a\nb");
+    }
+
+    #[test]
+    fn html_tables_and_code() {
+        let table = "<table><tr><td>a</td><td>b</td></tr><tr><td>c</td><td>d</td></tr></table>";
+        assert_eq!(html_to_text(table), "a\tb\nc\td");
+        assert_eq!(
+            html_to_markdown(table),
+            "| a | b |\n| --- | --- |\n| c | d |"
+        );
+        assert_eq!(
+            html_to_markdown("<pre><code>let x = 1;</code></pre>"),
+            "```\nlet x = 1;\n```"
+        );
+    }
+}
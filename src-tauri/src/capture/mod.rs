@@ -0,0 +1,447 @@
+use crate::commands::settings::{AppSettings, CaptureMatchers, ConcealedPolicy, DuplicatePolicy};
+use crate::database::{Database, DisplayClipboardItem, NewClipboardItem};
+use crate::events::{self, DeleteReason};
+use chrono::Utc;
+use coalesce::Coalescer;
+use rate_limit::RateLimiter;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use tauri::{async_runtime::JoinHandle, AppHandle, Emitter};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::oneshot;
+use tracing::{debug, error, info, warn};
+
+pub mod app_filter;
+pub mod classifier;
+pub mod coalesce;
+pub mod concealed;
+pub mod files;
+pub mod formats;
+pub mod health;
+pub mod imaging;
+pub mod lifecycle;
+pub mod markup;
+pub mod own_writes;
+pub mod pause;
+pub mod poller;
+pub mod primary;
+pub mod rate_limit;
+pub mod rules;
+pub mod sensitive;
+pub mod size_limit;
+pub mod source_app;
+
+/// キャプチャキューの容量（これを超えるとウォッチャー側で待機する）
+const CAPTURE_QUEUE_CAPACITY: usize = 64;
+
+/// キューが満杯の場合にウォッチャースレッドが待機する最大時間
+const ENQUEUE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 停止時にワーカーのキュー消化を待つ最大時間
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 有効期限切れアイテムを削除する間隔
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 機密データのコピーを伏せて保存する際の表示内容
+const CONCEALED_PLACEHOLDER: &str = "[機密データ（パスワードマネージャーからのコピー）]";
+
+// 破棄されたキャプチャイベントの累計
+static DROPPED_EVENTS: AtomicU64 = AtomicU64::new(0);
+
+// 稼働中のキャプチャワーカー
+static CAPTURE_WORKER: StdMutex<Option<CaptureWorker>> = StdMutex::new(None);
+
+/// ウォッチャースレッドで取得したクリップボードのスナップショット
+#[derive(Debug, Clone)]
+pub struct CaptureEvent {
+    pub item: NewClipboardItem,
+    pub concealed: bool, // パスワードマネージャーの機密ヒント付き（内容は未取得）
+}
+
+/// キャプチャワーカーへの送信口（ウォッチャー側が保持）
+#[derive(Clone)]
+pub struct CaptureSender {
+    app: AppHandle,
+    sender: mpsc::Sender<CaptureEvent>,
+}
+
+impl CaptureSender {
+    /// イベントをキューに投入する
+    ///
+    /// キューが満杯の場合は呼び出し元スレッドを一定時間ブロックして空きを待ち、
+    /// それでも投入できなければイベントを破棄して通知する。
+    pub fn enqueue(&self, event: CaptureEvent) -> bool {
+        let event = match self.sender.try_send(event) {
+            Ok(()) => return true,
+            Err(TrySendError::Full(event)) => event,
+            Err(TrySendError::Closed(_)) => {
+                report_dropped(&self.app, "キャプチャワーカー停止済み");
+                return false;
+            }
+        };
+
+        warn!("キャプチャキューが満杯のため待機します");
+        match tauri::async_runtime::block_on(self.sender.send_timeout(event, ENQUEUE_TIMEOUT)) {
+            Ok(()) => true,
+            Err(_) => {
+                report_dropped(&self.app, "キャプチャキューが満杯");
+                false
+            }
+        }
+    }
+}
+
+/// 常駐キャプチャワーカー
+struct CaptureWorker {
+    sender: mpsc::Sender<CaptureEvent>,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+/// キャプチャワーカーを起動し、送信口を返す（起動済みの場合は既存のワーカーを使う）
+pub fn start_worker(app: AppHandle, db: Arc<Database>) -> CaptureSender {
+    let mut worker = CAPTURE_WORKER.lock().unwrap();
+
+    if let Some(existing) = worker.as_ref() {
+        return CaptureSender {
+            app,
+            sender: existing.sender.clone(),
+        };
+    }
+
+    let (sender, receiver) = mpsc::channel(CAPTURE_QUEUE_CAPACITY);
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let handle = tauri::async_runtime::spawn(run_worker(app.clone(), db, receiver, shutdown_rx));
+
+    *worker = Some(CaptureWorker {
+        sender: sender.clone(),
+        shutdown,
+        handle,
+    });
+    info!("キャプチャワーカー起動");
+
+    CaptureSender { app, sender }
+}
+
+/// キャプチャワーカーを停止する（キューに残ったイベントは処理してから終了）
+pub async fn stop_worker() {
+    let worker = CAPTURE_WORKER.lock().unwrap().take();
+    let Some(worker) = worker else {
+        return;
+    };
+
+    let _ = worker.shutdown.send(());
+    drop(worker.sender);
+
+    match tokio::time::timeout(SHUTDOWN_TIMEOUT, worker.handle).await {
+        Ok(_) => info!("キャプチャワーカー停止"),
+        Err(_) => warn!("キャプチャワーカーの停止がタイムアウトしました"),
+    }
+}
+
+/// 破棄されたキャプチャイベントの累計を取得
+pub fn dropped_event_count() -> u64 {
+    DROPPED_EVENTS.load(Ordering::Relaxed)
+}
+
+/// イベント破棄を記録してフロントエンドに通知
+fn report_dropped(app: &AppHandle, reason: &str) {
+    let total = DROPPED_EVENTS.fetch_add(1, Ordering::Relaxed) + 1;
+    warn!(reason, total, "キャプチャイベント破棄");
+
+    let payload = serde_json::json!({ "reason": reason, "dropped_total": total });
+    if let Err(e) = app.emit("clipboard-capture-dropped", payload) {
+        error!("イベント送信エラー: {}", e);
+    }
+}
+
+/// キューからイベントを1件ずつ順番に処理するワーカー本体
+///
+/// 連続した変更は統合してから保存し、コピー元アプリごとの記録頻度を制限する。
+async fn run_worker(
+    app: AppHandle,
+    db: Arc<Database>,
+    mut receiver: mpsc::Receiver<CaptureEvent>,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let mut coalescer = Coalescer::default();
+    let mut rate_limiter = RateLimiter::default();
+
+    loop {
+        let flush_at = coalescer.flush_at();
+        let coalesce_timeout = async {
+            match flush_at {
+                Some(flush_at) => tokio::time::sleep_until(flush_at).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            biased;
+            event = receiver.recv() => match event {
+                Some(event) => {
+                    let settings = AppSettings::cached().await.unwrap_or_default();
+                    let window = Duration::from_millis(settings.capture_coalesce_window_ms);
+                    if window.is_zero() {
+                        capture_event(&app, &db, &mut rate_limiter, event).await;
+                    } else if let Some(previous) = coalescer.push(event, window) {
+                        capture_event(&app, &db, &mut rate_limiter, previous).await;
+                    }
+                }
+                None => break,
+            },
+            _ = coalesce_timeout => {
+                if let Some(event) = coalescer.take() {
+                    capture_event(&app, &db, &mut rate_limiter, event).await;
+                }
+            }
+            _ = &mut shutdown => {
+                // 新規投入を締め切り、残りを処理してから終了
+                receiver.close();
+                while let Some(event) = receiver.recv().await {
+                    if let Some(previous) = coalescer.push(event, Duration::ZERO) {
+                        capture_event(&app, &db, &mut rate_limiter, previous).await;
+                    }
+                }
+                break;
+            }
+        }
+    }
+
+    // 統合待ちのイベントも保存してから終了
+    if let Some(event) = coalescer.take() {
+        capture_event(&app, &db, &mut rate_limiter, event).await;
+    }
+}
+
+/// 記録頻度の上限を確認してからイベントを処理
+async fn capture_event(
+    app: &AppHandle,
+    db: &Arc<Database>,
+    rate_limiter: &mut RateLimiter,
+    event: CaptureEvent,
+) {
+    // イベントの処理中は同じ設定を使う
+    let settings = AppSettings::cached().await.unwrap_or_default();
+    let matchers = AppSettings::matchers().await;
+
+    // ClipOne自身の書き込みは新しいアイテムにしない（使用の記録は書き戻したコマンドで行う）
+    if !event.concealed && own_writes::take_match(&event.item) {
+        info!("ClipOneからの書き込みのため保存スキップ");
+        return;
+    }
+
+    if !rate_limiter.allow(
+        event.item.source.name.as_deref(),
+        settings.capture_rate_limit_per_minute,
+    ) {
+        report_dropped(app, "コピー元アプリの記録頻度が上限を超過");
+        return;
+    }
+
+    process_event(app, db, &settings, &matchers, event).await;
+}
+
+/// 履歴のアイテムをクリップボードに書き戻したことを使用として記録してフロントエンドに通知
+///
+/// 書き戻した内容が現在のクリップボードと同じ場合は変更通知が届かないため、
+/// 監視側ではなく書き戻したコマンドから呼ぶ。
+pub async fn record_item_use(app: &AppHandle, db: &Database, item_id: &str) {
+    let settings = AppSettings::cached().await.unwrap_or_default();
+    match db
+        .record_item_use(item_id, settings.move_reused_item_to_top)
+        .await
+    {
+        Ok(item) => {
+            info!(
+                "履歴のアイテムを使用: {} (使用回数: {})",
+                item.id, item.use_count
+            );
+            events::item_updated(app, &item.id);
+            emit_clipboard_updated(app, &DisplayClipboardItem::from(item));
+        }
+        Err(e) => error!("使用記録エラー: {}", e),
+    }
+}
+
+/// キャプチャイベントを重複チェックしてデータベースに保存
+async fn process_event(
+    app: &AppHandle,
+    db: &Arc<Database>,
+    settings: &AppSettings,
+    matchers: &CaptureMatchers,
+    event: CaptureEvent,
+) {
+    let mut item = event.item;
+    item.blob_threshold = Some(settings.blob_threshold_kb as usize * 1024);
+
+    // 機密ヒント付きのコピーは設定に従って破棄するか、内容を伏せて一定時間だけ保存
+    if event.concealed {
+        match settings.concealed_policy {
+            ConcealedPolicy::Skip => info!("機密データのため保存スキップ"),
+            ConcealedPolicy::Redact => {
+                let ttl_ms = settings.concealed_placeholder_ttl_secs as i64 * 1000;
+                item.primary_format = "text/plain".to_string();
+                item.format_contents = HashMap::from([(
+                    item.primary_format.clone(),
+                    CONCEALED_PLACEHOLDER.to_string(),
+                )]);
+                item.binary_contents.clear();
+                item.images.clear();
+                item.expires_at = Some(Utc::now().timestamp_millis() + ttl_ms);
+                save_item(app, db, &item).await;
+            }
+        }
+        return;
+    }
+
+    // 形式ごとのサイズ上限を適用（切り詰め・BLOBストアへの退避・保存しない）
+    if !size_limit::enforce(&mut item, &settings.content_size_limits) {
+        report_dropped(app, "サイズ上限を超過");
+        return;
+    }
+
+    // 機密データを検出した場合はフラグを立て、設定された時間後に自動削除
+    if settings.sensitive_detection_enabled {
+        item.sensitive_kind = item
+            .format_contents
+            .values()
+            .find_map(|text| matchers.sensitive.detect(text));
+
+        if let Some(kind) = &item.sensitive_kind {
+            info!(kind = %kind, "機密データを検出");
+            if settings.sensitive_ttl_secs > 0 {
+                let ttl_ms = settings.sensitive_ttl_secs as i64 * 1000;
+                item.expires_at = Some(Utc::now().timestamp_millis() + ttl_ms);
+            }
+        }
+    }
+
+    // テキストを提供しないアプリのHTML・RTFから検索・プレビュー用のテキストを派生
+    markup::add_derived_formats(&mut item, settings.derive_markdown_from_html);
+
+    // テキストの内容の種類を判定（URL・JSON・コードなど）
+    item.classify();
+
+    // ユーザー定義のキャプチャルールを上から順に評価（タグ・お気に入り・有効期限・テキストの変換）
+    let outcome = matchers.capture_rules.evaluate(&mut item);
+    if outcome.dropped {
+        info!(
+            "キャプチャルールにより保存スキップ: {:?}",
+            outcome.matched_rules
+        );
+        return;
+    }
+    item.tags = outcome.tags;
+    item.is_favorite = outcome.favorite;
+    if let Some(ttl_secs) = outcome.ttl_secs {
+        let expires_at = Utc::now().timestamp_millis() + ttl_secs as i64 * 1000;
+        item.expires_at = Some(
+            item.expires_at
+                .map_or(expires_at, |current| current.min(expires_at)),
+        );
+    }
+
+    // ファイル・ファイルパスは存在・サイズなどを取得時点で解決
+    item.files = files::resolve_item(&item);
+
+    // コンテンツハッシュで既存アイテムを検索（重複ポリシーが「すべて保存」以外の場合）
+    if settings.duplicate_policy != DuplicatePolicy::KeepAll {
+        let ignore_whitespace = settings.ignore_whitespace_duplicates;
+        let existing_id = match db
+            .find_item_id_by_hash(
+                &item.content_hash(ignore_whitespace),
+                ignore_whitespace,
+                item.selection,
+            )
+            .await
+        {
+            Ok(id) => id,
+            Err(e) => {
+                error!("重複チェックエラー: {}", e);
+                return;
+            }
+        };
+
+        if let Some(existing_id) = existing_id {
+            if settings.duplicate_policy == DuplicatePolicy::Ignore {
+                info!(id = %existing_id, "重複のため保存スキップ");
+                return;
+            }
+
+            match db.move_item_to_top(&existing_id).await {
+                Ok(item) => {
+                    info!(
+                        "既存アイテムを先頭に移動: {} (コピー回数: {})",
+                        item.id, item.copy_count
+                    );
+                    health::record_capture();
+                    events::item_updated(app, &item.id);
+                    emit_clipboard_updated(app, &DisplayClipboardItem::from(item));
+                }
+                Err(e) => error!("アイテム移動エラー: {}", e),
+            }
+            return;
+        }
+    }
+
+    save_item(app, db, &item).await;
+}
+
+/// アイテムを保存してフロントエンドに通知
+async fn save_item(app: &AppHandle, db: &Database, item: &NewClipboardItem) {
+    match db.save_clipboard_item_normalized(item).await {
+        Ok(saved_item) => {
+            info!(id = %saved_item.id, "データベース保存完了");
+            health::record_capture();
+            events::item_added(app, &saved_item.id);
+            emit_clipboard_updated(app, &DisplayClipboardItem::from(saved_item));
+        }
+        Err(e) => {
+            error!("データベース保存エラー: {}", e);
+            health::record_error(format!("データベース保存エラー: {}", e));
+        }
+    }
+}
+
+/// 有効期限切れアイテムを定期的に削除するタスクを起動（監視の停止中も動作し続ける）
+pub fn start_expiry_sweeper(app: AppHandle, db: Arc<Database>) {
+    tauri::async_runtime::spawn(async move {
+        let mut timer = tokio::time::interval(EXPIRY_SWEEP_INTERVAL);
+        loop {
+            timer.tick().await;
+            delete_expired_items(&app, &db).await;
+        }
+    });
+}
+
+/// 有効期限を過ぎたアイテムを削除してフロントエンドに通知
+async fn delete_expired_items(app: &AppHandle, db: &Arc<Database>) {
+    let ids = match db.delete_expired_items().await {
+        Ok(ids) => ids,
+        Err(e) => {
+            error!("期限切れアイテム削除エラー: {}", e);
+            return;
+        }
+    };
+
+    if ids.is_empty() {
+        return;
+    }
+
+    info!(count = ids.len(), "期限切れアイテムを削除");
+    events::items_deleted(app, ids, DeleteReason::Expired);
+}
+
+/// フロントエンドにイベント通知
+fn emit_clipboard_updated(app: &AppHandle, item: &DisplayClipboardItem) {
+    if let Err(e) = app.emit("clipboard-updated", item) {
+        error!("イベント送信エラー: {}", e);
+    } else {
+        debug!("フロントエンドにイベント通知成功");
+    }
+}
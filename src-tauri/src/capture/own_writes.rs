@@ -0,0 +1,125 @@
+use super::imaging;
+use crate::database::{hash, ClipboardItem, NewClipboardItem};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex as StdMutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// 書き込みからクリップボードの変更として届くまでの猶予（統合待ちの時間を含む）
+const OWN_WRITE_TTL: Duration = Duration::from_secs(5);
+
+/// ClipOne自身がクリップボードに書き込んだ内容
+#[derive(Debug, Clone)]
+struct OwnWrite {
+    token: u64,
+    fingerprints: HashMap<String, String>, // 書き込んだ形式ごとのハッシュ（画像はピクセルのハッシュ）
+    written_at: Instant,
+}
+
+// 変更通知を待っている書き込み
+static OWN_WRITES: StdMutex<Vec<OwnWrite>> = StdMutex::new(Vec::new());
+
+// 書き込みごとに払い出す番号
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// テキストの書き込みを登録
+pub fn register_text(text: &str) -> u64 {
+    let fingerprints = HashMap::from([("text/plain".to_string(), hash::content_hash(text, false))]);
+    register(fingerprints)
+}
+
+/// 保存済みアイテムの全形式の書き戻しを登録
+pub fn register_item(item: &ClipboardItem) -> u64 {
+    let fingerprints = item
+        .contents
+        .iter()
+        .filter_map(|content| {
+            let hash = match &content.content_blob {
+                Some(bytes) if is_reencoded_image(&content.format) => {
+                    match imaging::pixel_hash(bytes) {
+                        Ok(hash) => hash,
+                        // デコードできない画像は照合できないため登録しない
+                        Err(e) => {
+                            warn!("{}", e);
+                            return None;
+                        }
+                    }
+                }
+                Some(bytes) => hash::binary_hash(bytes),
+                None => hash::content_hash(&content.content, false),
+            };
+            Some((content.format.clone(), hash))
+        })
+        .collect();
+    register(fingerprints)
+}
+
+fn register(fingerprints: HashMap<String, String>) -> u64 {
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    let mut writes = OWN_WRITES.lock().unwrap();
+    writes.retain(|write| write.written_at.elapsed() < OWN_WRITE_TTL);
+    writes.push(OwnWrite {
+        token,
+        fingerprints,
+        written_at: Instant::now(),
+    });
+    token
+}
+
+/// 書き込みに失敗した場合などに登録を取り消す
+pub fn cancel(token: u64) {
+    OWN_WRITES
+        .lock()
+        .unwrap()
+        .retain(|write| write.token != token);
+}
+
+/// 取得した内容が自身の書き込みによるものなら、その登録を取り除いてtrueを返す
+///
+/// プライマリ形式のハッシュで照合する。画像はクリップボードを経由すると
+/// PNGが再エンコードされるため、デコードしたピクセルのハッシュで照合する。
+pub fn take_match(item: &NewClipboardItem) -> bool {
+    let format = &item.primary_format;
+    let mut writes = OWN_WRITES.lock().unwrap();
+    writes.retain(|write| write.written_at.elapsed() < OWN_WRITE_TTL);
+
+    // 同じ形式の書き込みを待っていなければハッシュ（画像のデコード）を計算しない
+    if !writes
+        .iter()
+        .any(|write| write.fingerprints.contains_key(format))
+    {
+        return false;
+    }
+
+    let hash = if is_reencoded_image(format) {
+        let Some(bytes) = item.binary_contents.get(format) else {
+            return false;
+        };
+        match imaging::pixel_hash(bytes) {
+            Ok(hash) => hash,
+            Err(e) => {
+                warn!("{}", e);
+                return false;
+            }
+        }
+    } else {
+        item.content_hash(false)
+    };
+
+    match writes
+        .iter()
+        .position(|write| write.fingerprints.get(format) == Some(&hash))
+    {
+        Some(position) => {
+            writes.remove(position);
+            true
+        }
+        None => false,
+    }
+}
+
+/// クリップボードを経由すると再エンコードされる画像形式か（他の形式はそのまま書き戻される）
+fn is_reencoded_image(format: &str) -> bool {
+    format == "image/png"
+}
@@ -0,0 +1,148 @@
+use crate::capture::lifecycle;
+use crate::commands::clipboard;
+use crate::events::{self, AppEvent};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use tracing::info;
+
+/// 一時停止の期間
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseDuration {
+    /// 5分間
+    FiveMinutes,
+    /// 1時間
+    OneHour,
+    /// アプリを再起動するまで
+    UntilRestart,
+}
+
+impl PauseDuration {
+    /// 自動再開までの時間（再起動までの場合はNone）
+    fn duration(self) -> Option<Duration> {
+        match self {
+            PauseDuration::FiveMinutes => Some(Duration::from_secs(5 * 60)),
+            PauseDuration::OneHour => Some(Duration::from_secs(60 * 60)),
+            PauseDuration::UntilRestart => None,
+        }
+    }
+}
+
+/// 一時停止の状態
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PauseStatus {
+    pub paused: bool,
+    pub paused_until: Option<i64>, // 自動再開の予定時刻（ミリ秒、再起動までの場合はnull）
+}
+
+/// 一時停止中の状態（世代番号で古い自動再開タイマーを無効化する）
+struct PauseState {
+    until: Option<i64>,
+    generation: u64,
+}
+
+// 一時停止状態（Noneなら記録中）
+static PAUSE: StdMutex<Option<PauseState>> = StdMutex::new(None);
+
+// 一時停止のたびに進める世代番号
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// キャプチャを一時停止（期間付きの場合は期限到達で自動再開）
+pub fn pause(app: &AppHandle, duration: PauseDuration) -> PauseStatus {
+    let until = duration
+        .duration()
+        .map(|d| chrono::Utc::now().timestamp_millis() + d.as_millis() as i64);
+
+    info!("キャプチャを一時停止しました: {:?}", duration);
+    pause_until(app, until);
+    lifecycle::set_paused_until(until);
+
+    emit_state(app);
+    status()
+}
+
+/// 前回終了時の期限付きの一時停止を、期限が過ぎていなければ引き継ぐ（起動時）
+///
+/// 再起動までの一時停止は名前どおり再起動で解除されるため引き継がない。
+pub fn restore(app: &AppHandle) {
+    let Some(until) = lifecycle::load().paused_until else {
+        return;
+    };
+    if until <= chrono::Utc::now().timestamp_millis() {
+        lifecycle::set_paused_until(None);
+        return;
+    }
+
+    info!("前回の一時停止を引き継ぎます（再開予定: {}）", until);
+    pause_until(app, Some(until));
+    emit_state(app);
+}
+
+/// 指定時刻まで一時停止し、期限付きの場合は自動再開のタイマーを設定
+fn pause_until(app: &AppHandle, until: Option<i64>) {
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+    *PAUSE.lock().unwrap() = Some(PauseState { until, generation });
+
+    if let Some(until) = until {
+        let remaining = (until - chrono::Utc::now().timestamp_millis()).max(0) as u64;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(remaining)).await;
+
+            // 待機中に再開・再度一時停止された場合は何もしない
+            let expired = {
+                let mut state = PAUSE.lock().unwrap();
+                let current = state.as_ref().is_some_and(|s| s.generation == generation);
+                if current {
+                    *state = None;
+                }
+                current
+            };
+            if expired {
+                info!("一時停止の期限に達したためキャプチャを再開します");
+                lifecycle::set_paused_until(None);
+                emit_state(&app);
+            }
+        });
+    }
+}
+
+/// 一時停止を解除（一時停止中でなければfalse）
+pub fn resume(app: &AppHandle) -> bool {
+    let was_paused = PAUSE.lock().unwrap().take().is_some();
+    if was_paused {
+        info!("キャプチャを再開しました");
+        lifecycle::set_paused_until(None);
+        emit_state(app);
+    }
+    was_paused
+}
+
+/// 現在の一時停止状態
+pub fn status() -> PauseStatus {
+    let now = chrono::Utc::now().timestamp_millis();
+    match PAUSE.lock().unwrap().as_ref() {
+        // タイマーの遅延に関わらず、期限を過ぎていれば記録中として扱う
+        Some(state) if state.until.is_none_or(|until| until > now) => PauseStatus {
+            paused: true,
+            paused_until: state.until,
+        },
+        _ => PauseStatus::default(),
+    }
+}
+
+/// 一時停止中か
+pub fn is_paused() -> bool {
+    status().paused
+}
+
+/// 一時停止・再開をフロントエンドに通知
+fn emit_state(app: &AppHandle) {
+    events::emit(
+        app,
+        AppEvent::MonitoringStateChanged(clipboard::monitoring_status()),
+    );
+}
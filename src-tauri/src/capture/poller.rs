@@ -0,0 +1,99 @@
+use clipboard_rs::{Clipboard, ClipboardContext, ClipboardHandler};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+use tracing::error;
+
+/// ポーリング間隔の下限（ミリ秒）
+pub const MIN_POLLING_INTERVAL_MS: u64 = 100;
+
+/// 変更イベントを受け取れない環境向けに、一定間隔でクリップボードを確認するウォッチャー
+///
+/// clipboard-rsのClipboardWatcherContextと同じ使い方（add_handler → get_shutdown_channel → start_watch）。
+pub struct PollingWatcher<T: ClipboardHandler> {
+    interval: Duration,
+    handlers: Vec<T>,
+    stop_tx: Sender<()>,
+    stop_rx: Receiver<()>,
+}
+
+/// ポーリング監視の停止ハンドル
+pub struct PollingShutdown {
+    stop_tx: Sender<()>,
+}
+
+impl PollingShutdown {
+    /// 監視ループを停止（待機中でもすぐに抜ける）
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(());
+    }
+}
+
+impl<T: ClipboardHandler> PollingWatcher<T> {
+    pub fn new(interval_ms: u64) -> Self {
+        let (stop_tx, stop_rx) = mpsc::channel();
+        Self {
+            interval: Duration::from_millis(interval_ms.max(MIN_POLLING_INTERVAL_MS)),
+            handlers: Vec::new(),
+            stop_tx,
+            stop_rx,
+        }
+    }
+
+    pub fn add_handler(&mut self, handler: T) -> &mut Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    pub fn get_shutdown_channel(&self) -> PollingShutdown {
+        PollingShutdown {
+            stop_tx: self.stop_tx.clone(),
+        }
+    }
+
+    /// 停止されるまでブロッキングでポーリングを続ける
+    pub fn start_watch(&mut self) {
+        let ctx = match ClipboardContext::new() {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                error!("ClipboardContext作成エラー: {}", e);
+                return;
+            }
+        };
+
+        // 監視開始時点の内容は記録しない
+        let mut last_fingerprint = fingerprint(&ctx);
+
+        // 停止要求があるまで間隔ごとに確認
+        while let Err(RecvTimeoutError::Timeout) = self.stop_rx.recv_timeout(self.interval) {
+            let current = fingerprint(&ctx);
+            if current == last_fingerprint {
+                continue;
+            }
+            last_fingerprint = current;
+
+            for handler in self.handlers.iter_mut() {
+                handler.on_clipboard_change();
+            }
+        }
+    }
+}
+
+/// 変更検出用の軽量なフィンガープリント
+///
+/// X11ではセレクション取得時刻（TIMESTAMP）を使い、内容本体は読み取らない。
+/// TIMESTAMPが得られない環境ではテキストを比較する（画像のみの変更は形式一覧が変わった場合に検出）。
+fn fingerprint(ctx: &ClipboardContext) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    ctx.available_formats()
+        .unwrap_or_default()
+        .hash(&mut hasher);
+
+    match ctx.get_buffer("TIMESTAMP") {
+        Ok(timestamp) if !timestamp.is_empty() => timestamp.hash(&mut hasher),
+        _ => ctx.get_text().unwrap_or_default().hash(&mut hasher),
+    }
+
+    hasher.finish()
+}
@@ -0,0 +1,379 @@
+use crate::capture::app_filter::AppRuleAction;
+use crate::capture::{concealed, pause, source_app, CaptureEvent, CaptureSender};
+use crate::commands::settings::AppSettings;
+use crate::database::{hash, ClipboardSelection, NewClipboardItem};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex as StdMutex;
+use tracing::{debug, info};
+
+// PRIMARYセレクション監視スレッドの停止ハンドル
+static PRIMARY_SHUTDOWN: StdMutex<Option<Sender<()>>> = StdMutex::new(None);
+
+/// PRIMARYセレクション（テキスト選択）の監視を開始
+///
+/// 選択範囲はドラッグ中も変化し続けるため、最後の変更からdebounce_msだけ落ち着いてから読み取る。
+pub fn start(capture: CaptureSender, debounce_ms: u64) {
+    stop();
+
+    #[cfg(target_os = "linux")]
+    {
+        let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+        *PRIMARY_SHUTDOWN.lock().unwrap() = Some(stop_tx);
+
+        std::thread::spawn(move || {
+            let mut handler = PrimaryCapture {
+                capture,
+                last_content_hash: String::new(),
+            };
+            let debounce = std::time::Duration::from_millis(debounce_ms);
+            x11::supervise(&mut handler, debounce, &stop_rx);
+        });
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (capture, debounce_ms);
+        tracing::warn!("PRIMARYセレクションはこのプラットフォームでは利用できません");
+    }
+}
+
+/// PRIMARYセレクションの監視を停止
+pub fn stop() {
+    if let Some(stop_tx) = PRIMARY_SHUTDOWN.lock().unwrap().take() {
+        let _ = stop_tx.send(());
+    }
+}
+
+/// 選択内容の読み取り（内容を読む前に形式だけを確認できるようにする）
+trait SelectionSource {
+    /// 提示されている形式名
+    fn targets(&mut self) -> Result<Vec<String>>;
+    /// 指定した形式の値（提供されていない場合はNone）
+    fn read(&mut self, target: &str) -> Result<Option<Vec<u8>>>;
+    /// UTF-8テキスト（テキストとして提供されていない場合はNone）
+    fn read_text(&mut self) -> Result<Option<String>>;
+}
+
+/// 選択されたテキストをキャプチャパイプラインに渡す
+struct PrimaryCapture {
+    capture: CaptureSender,
+    last_content_hash: String,
+}
+
+impl PrimaryCapture {
+    /// CLIPBOARDと同じく、アプリ別キャプチャルールと機密ヒントを確認してから内容を読み取る
+    fn on_selection(&mut self, selection: &mut impl SelectionSource) -> Result<()> {
+        if pause::is_paused() {
+            return Ok(());
+        }
+
+        let source = source_app::detect(ClipboardSelection::Primary);
+        let matchers = tauri::async_runtime::block_on(AppSettings::matchers());
+        if matchers.app_filter.evaluate(&source) == AppRuleAction::Block {
+            info!("キャプチャルールにより記録しません: {:?}", source.name);
+            return Ok(());
+        }
+
+        // パスワード欄の選択など、機密ヒント付きの選択は内容を読み取らない
+        let targets = selection.targets()?;
+        if let Some(hint) =
+            concealed::find_hint_with(&targets, |hint| selection.read(hint).ok().flatten())
+        {
+            info!("機密ヒントを検出したため選択内容を読み取りません: {}", hint);
+            self.last_content_hash.clear();
+            self.capture.enqueue(CaptureEvent {
+                item: NewClipboardItem {
+                    source,
+                    selection: ClipboardSelection::Primary,
+                    ..Default::default()
+                },
+                concealed: true,
+            });
+            return Ok(());
+        }
+
+        let Some(text) = selection.read_text()? else {
+            return Ok(());
+        };
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+
+        // 同じ範囲の選択し直しは記録しない
+        let content_hash = hash::content_hash(&text, false);
+        if content_hash == self.last_content_hash {
+            return Ok(());
+        }
+        debug!(
+            "新しい選択テキスト: {}",
+            hash::log_summary(text.len(), &content_hash)
+        );
+        self.last_content_hash = content_hash;
+        let primary_format = "text/plain".to_string();
+        self.capture.enqueue(CaptureEvent {
+            item: NewClipboardItem {
+                format_contents: HashMap::from([(primary_format.clone(), text)]),
+                primary_format,
+                source,
+                selection: ClipboardSelection::Primary,
+                ..Default::default()
+            },
+            concealed: false,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::{PrimaryCapture, SelectionSource};
+    use crate::capture::health;
+    use crate::commands::clipboard::{
+        emit_monitoring_state, HEALTHY_RUN, RESTART_BACKOFF_INITIAL, RESTART_BACKOFF_MAX,
+    };
+    use anyhow::Result;
+    use std::sync::mpsc::{Receiver, RecvTimeoutError};
+    use std::time::{Duration, Instant};
+    use tracing::{error, info, warn};
+    use x11rb::connection::Connection;
+    use x11rb::errors::{ConnectionError, ReplyError};
+    use x11rb::protocol::xfixes::{ConnectionExt as _, SelectionEventMask};
+    use x11rb::protocol::xproto::{
+        Atom, AtomEnum, ConnectionExt as _, CreateWindowAux, GetPropertyReply, Window, WindowClass,
+    };
+    use x11rb::protocol::Event;
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::{COPY_DEPTH_FROM_PARENT, COPY_FROM_PARENT, CURRENT_TIME, NONE};
+
+    /// イベントを確認する間隔
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// 選択内容の読み取りを待つ最大時間
+    const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// 読み取る最大長（32ビット単位、1MB）
+    const MAX_READ_LENGTH: u32 = 256 * 1024;
+
+    x11rb::atom_manager! {
+        Atoms: AtomsCookie {
+            UTF8_STRING,
+            INCR,
+            TARGETS,
+            CLIPONE_PRIMARY,
+        }
+    }
+
+    /// PRIMARYセレクションを監視し、Xサーバーとの接続が切れた場合は待ち時間を延ばしながら再接続する
+    pub fn supervise(handler: &mut PrimaryCapture, debounce: Duration, stop_rx: &Receiver<()>) {
+        let mut backoff = RESTART_BACKOFF_INITIAL;
+
+        loop {
+            info!("PRIMARYセレクション監視開始");
+            let started = Instant::now();
+            let Err(e) = run(handler, debounce, stop_rx) else {
+                break;
+            };
+
+            let message = format!("PRIMARYセレクション監視エラー: {}", e);
+            error!("{}", message);
+            health::record_error(message);
+            emit_monitoring_state(&handler.capture.app);
+
+            if started.elapsed() >= HEALTHY_RUN {
+                backoff = RESTART_BACKOFF_INITIAL;
+            }
+            info!(
+                "{}秒後にPRIMARYセレクション監視を再開します",
+                backoff.as_secs()
+            );
+            // 待機中に停止された場合は再開しない
+            if !matches!(
+                stop_rx.recv_timeout(backoff),
+                Err(RecvTimeoutError::Timeout)
+            ) {
+                break;
+            }
+            backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+        }
+        info!("PRIMARYセレクション監視終了");
+    }
+
+    /// 停止要求があるまでPRIMARYセレクションの所有者変更を監視する
+    ///
+    /// 個々の選択の読み取りエラーは記録して監視を続け、接続が切れた場合のみエラーを返す。
+    pub fn run(
+        handler: &mut PrimaryCapture,
+        debounce: Duration,
+        stop_rx: &Receiver<()>,
+    ) -> Result<()> {
+        let (conn, screen_num) = x11rb::connect(None)?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn)?.reply()?;
+        conn.xfixes_query_version(5, 0)?.reply()?;
+
+        // 変更通知と選択内容の受け取りに使う非表示ウィンドウ
+        let window = conn.generate_id()?;
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )?;
+        conn.xfixes_select_selection_input(
+            window,
+            AtomEnum::PRIMARY.into(),
+            SelectionEventMask::SET_SELECTION_OWNER,
+        )?;
+        conn.flush()?;
+
+        // 最後に所有者が変わった時刻（読み取り待ち）
+        let mut pending: Option<Instant> = None;
+
+        while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(POLL_INTERVAL) {
+            while let Some(event) = conn.poll_for_event()? {
+                if let Event::XfixesSelectionNotify(_) = event {
+                    pending = Some(Instant::now());
+                }
+            }
+
+            // 選択操作が落ち着くまで待つ
+            let settled = pending.is_some_and(|since| since.elapsed() >= debounce);
+            if !settled {
+                continue;
+            }
+            pending = None;
+
+            let mut reader = Reader {
+                conn: &conn,
+                atoms: &atoms,
+                window,
+                pending: &mut pending,
+            };
+            if let Err(e) = handler.on_selection(&mut reader) {
+                if is_connection_error(&e) {
+                    return Err(e);
+                }
+                warn!("選択内容の読み取りエラー: {}", e);
+            }
+        }
+
+        conn.destroy_window(window)?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// Xサーバーとの接続自体のエラーか（個々のリクエストの失敗ではない）
+    fn is_connection_error(e: &anyhow::Error) -> bool {
+        e.downcast_ref::<ConnectionError>().is_some()
+            || matches!(
+                e.downcast_ref::<ReplyError>(),
+                Some(ReplyError::ConnectionError(_))
+            )
+    }
+
+    /// PRIMARYセレクションの読み取り（読み取り中の所有者変更はpendingに反映）
+    struct Reader<'a> {
+        conn: &'a RustConnection,
+        atoms: &'a Atoms,
+        window: Window,
+        pending: &'a mut Option<Instant>,
+    }
+
+    impl Reader<'_> {
+        /// 指定した形式に変換して読み取る（提供されていない・大きすぎる場合はNone）
+        fn convert(&mut self, target: Atom) -> Result<Option<GetPropertyReply>> {
+            let (conn, atoms, window) = (self.conn, self.atoms, self.window);
+            conn.convert_selection(
+                window,
+                AtomEnum::PRIMARY.into(),
+                target,
+                atoms.CLIPONE_PRIMARY,
+                CURRENT_TIME,
+            )?;
+            conn.flush()?;
+
+            let deadline = Instant::now() + READ_TIMEOUT;
+            loop {
+                match conn.poll_for_event()? {
+                    Some(Event::SelectionNotify(event)) if event.requestor == window => {
+                        // 指定した形式で提供されていない選択
+                        if event.property == NONE {
+                            return Ok(None);
+                        }
+
+                        let reply = conn
+                            .get_property(
+                                true,
+                                window,
+                                atoms.CLIPONE_PRIMARY,
+                                AtomEnum::ANY,
+                                0,
+                                MAX_READ_LENGTH,
+                            )?
+                            .reply()?;
+                        if reply.type_ == atoms.INCR {
+                            warn!("選択内容が大きすぎるため読み取りません");
+                            return Ok(None);
+                        }
+
+                        return Ok(Some(reply));
+                    }
+                    Some(Event::XfixesSelectionNotify(_)) => *self.pending = Some(Instant::now()),
+                    Some(_) => {}
+                    None if Instant::now() >= deadline => {
+                        warn!("選択内容の読み取りがタイムアウトしました");
+                        return Ok(None);
+                    }
+                    None => std::thread::sleep(Duration::from_millis(10)),
+                }
+            }
+        }
+    }
+
+    impl SelectionSource for Reader<'_> {
+        fn targets(&mut self) -> Result<Vec<String>> {
+            let Some(reply) = self.convert(self.atoms.TARGETS)? else {
+                return Ok(Vec::new());
+            };
+            let cookies = reply
+                .value32()
+                .into_iter()
+                .flatten()
+                .map(|atom| self.conn.get_atom_name(atom))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(cookies
+                .into_iter()
+                .filter_map(|cookie| cookie.reply().ok())
+                .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+                .collect())
+        }
+
+        fn read(&mut self, target: &str) -> Result<Option<Vec<u8>>> {
+            let atom = self
+                .conn
+                .intern_atom(true, target.as_bytes())?
+                .reply()?
+                .atom;
+            if atom == NONE {
+                return Ok(None);
+            }
+            Ok(self.convert(atom)?.map(|reply| reply.value))
+        }
+
+        fn read_text(&mut self) -> Result<Option<String>> {
+            Ok(self
+                .convert(self.atoms.UTF8_STRING)?
+                .map(|reply| String::from_utf8_lossy(&reply.value).into_owned()))
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 記録数を数える期間
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// コピー元アプリごとの記録頻度の上限（クリップボードを連続で書き換えるアプリ対策）
+#[derive(Default)]
+pub struct RateLimiter {
+    history: HashMap<String, VecDeque<Instant>>, // アプリごとの直近の記録時刻
+}
+
+impl RateLimiter {
+    /// 1分あたりの上限以内なら記録を数えてtrueを返す（上限0は無制限）
+    ///
+    /// コピー元アプリが分からない場合（Waylandなど）は制限しない。
+    /// 不明なアプリをまとめて数えると、すべてのアプリのコピーが1つの上限を共有してしまうため。
+    pub fn allow(&mut self, source: Option<&str>, limit_per_minute: u32) -> bool {
+        let Some(source) = source else {
+            return true;
+        };
+        if limit_per_minute == 0 {
+            return true;
+        }
+
+        let now = Instant::now();
+        let times = self.history.entry(source.to_string()).or_default();
+        while times
+            .front()
+            .is_some_and(|time| now.duration_since(*time) >= RATE_WINDOW)
+        {
+            times.pop_front();
+        }
+
+        if times.len() >= limit_per_minute as usize {
+            return false;
+        }
+        times.push_back(now);
+
+        // しばらく記録のないアプリの履歴は捨てる
+        self.history.retain(|_, times| {
+            times
+                .back()
+                .is_some_and(|time| now.duration_since(*time) < RATE_WINDOW)
+        });
+        true
+    }
+}
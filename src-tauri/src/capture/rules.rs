@@ -0,0 +1,403 @@
+use crate::capture::classifier::ContentKind;
+use crate::capture::formats;
+use crate::database::NewClipboardItem;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// 正規表現の照合対象の最大バイト数（巨大なテキストで時間がかからないように）
+const MAX_MATCH_BYTES: usize = 256 * 1024;
+
+/// URLから取り除くトラッキング用のクエリパラメータ（末尾の*は前方一致）
+const TRACKING_PARAMS: &[&str] = &[
+    "utm_*",
+    "fbclid",
+    "gclid",
+    "dclid",
+    "gbraid",
+    "wbraid",
+    "msclkid",
+    "yclid",
+    "twclid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "oly_anon_id",
+    "oly_enc_id",
+    "vero_id",
+];
+
+static URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"https?://[^\s<>"'`]+"#).expect("URLの正規表現が不正です"));
+
+/// キャプチャルールの条件（指定した条件をすべて満たす場合に一致、未指定の条件は問わない）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleConditions {
+    pub pattern: Option<String>,  // プライマリ形式のテキストの正規表現
+    pub kinds: Vec<ContentKind>,  // 内容の種類（いずれか）
+    pub formats: Vec<String>,     // 含む形式（いずれか、末尾の*で前方一致）
+    pub min_size: Option<u64>,    // プライマリ形式のサイズの下限（バイト）
+    pub max_size: Option<u64>,    // プライマリ形式のサイズの上限（バイト）
+    pub source_apps: Vec<String>, // コピー元のアプリ名・実行ファイル名（いずれか、大文字小文字を区別しない）
+}
+
+/// テキストの変換
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextTransform {
+    /// 前後の空白・改行を除去
+    Trim,
+    /// 連続する空白を1つにまとめる
+    CollapseWhitespace,
+    /// URLからトラッキング用のクエリパラメータ（utm_*など）を除去
+    StripTrackingParams,
+}
+
+/// キャプチャルールの動作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// 保存しない（以降のルールも評価しない）
+    Drop,
+    /// タグを付ける
+    Tag { tag: String },
+    /// お気に入りにする
+    Favorite,
+    /// 指定した秒数後に自動削除
+    SetTtl { ttl_secs: u64 },
+    /// テキストを変換してから保存
+    Transform { transform: TextTransform },
+}
+
+/// ユーザー定義のキャプチャルール
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub conditions: RuleConditions,
+    pub actions: Vec<RuleAction>,
+    #[serde(default)]
+    pub stop_processing: bool, // 一致した場合に以降のルールを評価しない
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// ルールを評価した結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RuleOutcome {
+    pub matched_rules: Vec<String>, // 一致したルールのID（評価順）
+    pub dropped: bool,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+    pub ttl_secs: Option<u64>, // 複数指定された場合は最短
+    pub transformed: bool,     // テキストが変換された
+}
+
+impl CaptureRule {
+    /// ルールの内容を検証（動作のないルールや不正な正規表現を拒否）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.actions.is_empty() {
+            return Err("ルールの動作が指定されていません".to_string());
+        }
+        for action in &self.actions {
+            if let RuleAction::Tag { tag } = action {
+                if tag.trim().is_empty() {
+                    return Err("タグが空です".to_string());
+                }
+            }
+        }
+        if let Some(pattern) = &self.conditions.pattern {
+            compile(pattern).map_err(|e| format!("ルールの正規表現が不正です: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// アイテムがこのルールの条件に一致するか（条件の正規表現はコンパイル済みのものを使う）
+    fn matches(&self, pattern: Option<&Regex>, item: &NewClipboardItem) -> bool {
+        let conditions = &self.conditions;
+
+        if !conditions.kinds.is_empty() {
+            let kind = item.classification.as_ref().map(|c| c.kind);
+            if !kind.is_some_and(|kind| conditions.kinds.contains(&kind)) {
+                return false;
+            }
+        }
+
+        if !conditions.formats.is_empty() {
+            let mut item_formats = item
+                .format_contents
+                .keys()
+                .chain(item.binary_contents.keys());
+            let has_format = item_formats.any(|format| {
+                conditions
+                    .formats
+                    .iter()
+                    .any(|pattern| formats::matches_pattern(pattern, format))
+            });
+            if !has_format {
+                return false;
+            }
+        }
+
+        let size = primary_size(item);
+        if conditions.min_size.is_some_and(|min| size < min)
+            || conditions.max_size.is_some_and(|max| size > max)
+        {
+            return false;
+        }
+
+        if !conditions.source_apps.is_empty() {
+            let source = &item.source;
+            let executable_name = source.executable.as_deref().map(|executable| {
+                std::path::Path::new(executable)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or(executable)
+            });
+            let candidates = [source.name.as_deref(), executable_name];
+            let from_app = conditions.source_apps.iter().any(|app| {
+                candidates
+                    .iter()
+                    .flatten()
+                    .any(|candidate| candidate.eq_ignore_ascii_case(app))
+            });
+            if !from_app {
+                return false;
+            }
+        }
+
+        match pattern {
+            Some(regex) => item
+                .format_contents
+                .get(&item.primary_format)
+                .map(|text| crate::database::truncate_to_char_boundary(text, MAX_MATCH_BYTES))
+                .is_some_and(|text| regex.is_match(text)),
+            None => true,
+        }
+    }
+}
+
+/// 正規表現をコンパイルしたキャプチャルール（設定の変更時に作り直す）
+pub struct RuleSet {
+    rules: Vec<(CaptureRule, Option<Regex>)>,
+}
+
+impl RuleSet {
+    /// 有効なルールの正規表現をコンパイル（不正なルールは警告して無視）
+    pub fn new(rules: &[CaptureRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(
+                |rule| match rule.conditions.pattern.as_deref().map(compile) {
+                    None => Some((rule.clone(), None)),
+                    Some(Ok(regex)) => Some((rule.clone(), Some(regex))),
+                    Some(Err(e)) => {
+                        warn!("キャプチャルールの正規表現が不正です ({}): {}", rule.id, e);
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        Self { rules }
+    }
+
+    /// ルールを上から順に評価し、一致したルールの動作をまとめる
+    ///
+    /// テキストの変換はその場でアイテムに適用し、以降のルールは変換後の内容で評価する。
+    /// 保存しないルールに一致した時点で評価を終える。
+    pub fn evaluate(&self, item: &mut NewClipboardItem) -> RuleOutcome {
+        let mut outcome = RuleOutcome::default();
+
+        for (rule, pattern) in &self.rules {
+            if !rule.matches(pattern.as_ref(), item) {
+                continue;
+            }
+            outcome.matched_rules.push(rule.id.clone());
+
+            for action in &rule.actions {
+                match action {
+                    RuleAction::Drop => {
+                        outcome.dropped = true;
+                        return outcome;
+                    }
+                    RuleAction::Tag { tag } => {
+                        let tag = tag.trim().to_string();
+                        if !outcome.tags.contains(&tag) {
+                            outcome.tags.push(tag);
+                        }
+                    }
+                    RuleAction::Favorite => outcome.favorite = true,
+                    RuleAction::SetTtl { ttl_secs } => {
+                        outcome.ttl_secs = Some(
+                            outcome
+                                .ttl_secs
+                                .map_or(*ttl_secs, |current| current.min(*ttl_secs)),
+                        );
+                    }
+                    RuleAction::Transform { transform } => {
+                        if apply_transform(item, *transform) {
+                            outcome.transformed = true;
+                            // 変換後の内容で種類を判定し直す
+                            item.classify();
+                        }
+                    }
+                }
+            }
+
+            if rule.stop_processing {
+                break;
+            }
+        }
+
+        outcome
+    }
+}
+
+/// テキスト形式（HTML・RTFを除く）に変換を適用し、内容が変わったかを返す
+fn apply_transform(item: &mut NewClipboardItem, transform: TextTransform) -> bool {
+    let mut changed = false;
+
+    for (format, text) in item.format_contents.iter_mut() {
+        if !format.starts_with("text/") || format == "text/html" || format == "text/rtf" {
+            continue;
+        }
+
+        let transformed = match transform {
+            TextTransform::Trim => text.trim().to_string(),
+            TextTransform::CollapseWhitespace => {
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            }
+            TextTransform::StripTrackingParams => strip_tracking_params(text),
+        };
+        if transformed != *text {
+            *text = transformed;
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// テキスト中のURLからトラッキング用のクエリパラメータを除去
+pub fn strip_tracking_params(text: &str) -> String {
+    URL.replace_all(text, |caps: &regex::Captures| strip_url(&caps[0]))
+        .into_owned()
+}
+
+fn strip_url(url: &str) -> String {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = rest.split_once('?') else {
+        return url.to_string();
+    };
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|param| {
+            let key = param.split('=').next().unwrap_or_default();
+            !param.is_empty()
+                && !TRACKING_PARAMS
+                    .iter()
+                    .any(|pattern| formats::matches_pattern(pattern, key))
+        })
+        .collect();
+
+    let mut stripped = base.to_string();
+    if !kept.is_empty() {
+        stripped.push('?');
+        stripped.push_str(&kept.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        stripped.push('#');
+        stripped.push_str(fragment);
+    }
+    stripped
+}
+
+/// プライマリ形式のサイズ（バイト）
+fn primary_size(item: &NewClipboardItem) -> u64 {
+    let text_size = item
+        .format_contents
+        .get(&item.primary_format)
+        .map(|text| text.len());
+    let binary_size = item
+        .binary_contents
+        .get(&item.primary_format)
+        .map(|bytes| bytes.len());
+    text_size.or(binary_size).unwrap_or_default() as u64
+}
+
+/// ルールの正規表現をコンパイル（大文字小文字を区別しない、複数行モード）
+fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .multi_line(true)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn tag_rule(id: &str, pattern: &str) -> CaptureRule {
+        CaptureRule {
+            id: id.to_string(),
+            name: String::new(),
+            conditions: RuleConditions {
+                pattern: Some(pattern.to_string()),
+                ..Default::default()
+            },
+            actions: vec![RuleAction::Tag {
+                tag: id.to_string(),
+            }],
+            stop_processing: false,
+            enabled: true,
+        }
+    }
+
+    fn text_item(text: &str) -> NewClipboardItem {
+        NewClipboardItem {
+            primary_format: "text/plain".to_string(),
+            format_contents: HashMap::from([("text/plain".to_string(), text.to_string())]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn compiled_patterns_match_case_insensitively_per_line() {
+        let rules = RuleSet::new(&[tag_rule("ticket", "^jira-\\d+$")]);
+
+        let outcome = rules.evaluate(&mut text_item("note\nJIRA-42"));
+        assert_eq!(outcome.tags, vec!["ticket"]);
+
+        let outcome = rules.evaluate(&mut text_item("jira-x"));
+        assert!(outcome.matched_rules.is_empty());
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected_and_skipped() {
+        let invalid = tag_rule("broken", "(");
+        assert!(invalid.validate().is_err());
+
+        let rules = RuleSet::new(&[invalid, tag_rule("any", ".")]);
+        let outcome = rules.evaluate(&mut text_item("("));
+        assert_eq!(outcome.matched_rules, vec!["any"]);
+    }
+}
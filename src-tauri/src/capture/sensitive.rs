@@ -0,0 +1,265 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// 1形式あたりの検査対象の最大バイト数（巨大なテキストで時間がかからないように）
+const MAX_SCAN_BYTES: usize = 256 * 1024;
+
+/// 機密データの検出ルール（設定でユーザー定義ルールを追加できる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensitiveRule {
+    pub name: String,    // 検出時に記録される種類名
+    pub pattern: String, // 正規表現
+    #[serde(default)]
+    pub validator: Option<SensitiveValidator>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 正規表現に一致した文字列の追加検証
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveValidator {
+    /// Luhnチェックサム
+    Luhn,
+    /// 国際ブランドの発行者番号（IIN）と桁数、Luhnチェックサム（クレジットカード番号）
+    CreditCard,
+    /// PEMのBEGIN/ENDヘッダーが対になっている（秘密鍵）
+    Pem,
+}
+
+/// コンパイル済みのルール
+struct CompiledRule {
+    name: String,
+    regex: Regex,
+    validator: Option<SensitiveValidator>,
+}
+
+/// 組み込みルール（種類名, 正規表現, 検証）
+const BUILTIN_RULES: &[(&str, &str, Option<SensitiveValidator>)] = &[
+    (
+        "private_key",
+        r"-----BEGIN (?:[A-Z]+ )*PRIVATE KEY(?: BLOCK)?-----",
+        Some(SensitiveValidator::Pem),
+    ),
+    ("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b", None),
+    (
+        "github_token",
+        r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})\b",
+        None,
+    ),
+    ("slack_token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}", None),
+    ("google_api_key", r"\bAIza[0-9A-Za-z_-]{35}\b", None),
+    ("api_secret_key", r"\bsk-[A-Za-z0-9_-]{20,}", None),
+    (
+        "jwt",
+        r"\beyJ[A-Za-z0-9_-]{5,}\.eyJ[A-Za-z0-9_-]{5,}\.[A-Za-z0-9_-]{10,}",
+        None,
+    ),
+    (
+        "credit_card",
+        r"\b\d(?:[ -]?\d){12,18}\b",
+        Some(SensitiveValidator::CreditCard),
+    ),
+];
+
+static BUILTIN: LazyLock<Vec<CompiledRule>> = LazyLock::new(|| {
+    BUILTIN_RULES
+        .iter()
+        .map(|(name, pattern, validator)| CompiledRule {
+            name: name.to_string(),
+            regex: Regex::new(pattern).expect("組み込みルールの正規表現が不正です"),
+            validator: *validator,
+        })
+        .collect()
+});
+
+/// 機密データ検出器（組み込みルール + ユーザー定義ルール）
+pub struct SensitiveDetector {
+    custom_rules: Vec<CompiledRule>,
+}
+
+impl SensitiveDetector {
+    /// ユーザー定義ルールをコンパイルして検出器を作成（不正なルールは警告して無視）
+    pub fn new(custom_rules: &[SensitiveRule]) -> Self {
+        let custom_rules = custom_rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some(CompiledRule {
+                    name: rule.name.clone(),
+                    regex,
+                    validator: rule.validator,
+                }),
+                Err(e) => {
+                    warn!("機密データ検出ルールが不正です ({}): {}", rule.name, e);
+                    None
+                }
+            })
+            .collect();
+
+        Self { custom_rules }
+    }
+
+    /// テキストが機密データを含む場合、一致したルールの種類名を返す
+    pub fn detect(&self, text: &str) -> Option<String> {
+        let text = crate::database::truncate_to_char_boundary(text, MAX_SCAN_BYTES);
+
+        BUILTIN
+            .iter()
+            .chain(self.custom_rules.iter())
+            .find(|rule| {
+                rule.regex.find_iter(text).any(|m| match rule.validator {
+                    Some(SensitiveValidator::Luhn) => luhn_valid(m.as_str()),
+                    Some(SensitiveValidator::CreditCard) => card_number_valid(m.as_str()),
+                    Some(SensitiveValidator::Pem) => pem_valid(m.as_str(), &text[m.end()..]),
+                    None => true,
+                })
+            })
+            .map(|rule| rule.name.clone())
+    }
+}
+
+/// ユーザー定義ルールの正規表現を検証（設定保存時に使用）
+pub fn validate_rules(rules: &[SensitiveRule]) -> Result<(), String> {
+    for rule in rules {
+        if rule.name.trim().is_empty() {
+            return Err("機密データ検出ルールの名前が空です".to_string());
+        }
+        Regex::new(&rule.pattern)
+            .map_err(|e| format!("機密データ検出ルールが不正です ({}): {}", rule.name, e))?;
+    }
+    Ok(())
+}
+
+/// プレビュー用に内容を伏せる（先頭の数文字だけ残す）
+pub fn mask(text: &str) -> String {
+    const VISIBLE_CHARS: usize = 4;
+    const MAX_MASK_CHARS: usize = 16;
+
+    let trimmed = text.trim();
+    let visible: String = trimmed.chars().take(VISIBLE_CHARS).collect();
+    let hidden = trimmed.chars().count().saturating_sub(VISIBLE_CHARS);
+    format!("{}{}", visible, "•".repeat(hidden.clamp(1, MAX_MASK_CHARS)))
+}
+
+/// Luhnアルゴリズムでカード番号を検証
+fn luhn_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| {
+            if i % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                digit
+            }
+        })
+        .sum();
+
+    sum.is_multiple_of(10)
+}
+
+/// 国際ブランドのIINと桁数に一致し、Luhnチェックサムが正しいか検証
+///
+/// 桁数とチェックサムだけでは任意の数字列（ミリ秒のタイムスタンプや注文番号など）の
+/// 約1割が一致してしまうため、発行者番号で絞り込む。
+fn card_number_valid(candidate: &str) -> bool {
+    let digits: String = candidate.chars().filter(char::is_ascii_digit).collect();
+    let len = digits.len();
+    let prefix = |n: usize| digits.get(..n).and_then(|p| p.parse::<u32>().ok());
+
+    let known_brand = match digits.as_bytes().first() {
+        // Visa
+        Some(b'4') => matches!(len, 13 | 16 | 19),
+        // Mastercard
+        Some(b'5') => matches!(prefix(2), Some(51..=55)) && len == 16,
+        Some(b'2') => matches!(prefix(4), Some(2221..=2720)) && len == 16,
+        // American Express / JCB / Diners Club
+        Some(b'3') => match prefix(2) {
+            Some(34 | 37) => len == 15,
+            Some(35) => matches!(prefix(4), Some(3528..=3589)) && (16..=19).contains(&len),
+            Some(36 | 38 | 39) => (14..=19).contains(&len),
+            Some(30) => matches!(prefix(3), Some(300..=305)) && (14..=19).contains(&len),
+            _ => false,
+        },
+        // Discover / UnionPay
+        Some(b'6') => {
+            (prefix(4) == Some(6011)
+                || matches!(prefix(3), Some(644..=649))
+                || matches!(prefix(2), Some(62 | 65)))
+                && (16..=19).contains(&len)
+        }
+        _ => false,
+    };
+
+    known_brand && luhn_valid(&digits)
+}
+
+/// BEGINヘッダー以降に対応するENDヘッダーがあるか検証
+fn pem_valid(begin_header: &str, rest: &str) -> bool {
+    let label = begin_header
+        .trim_start_matches("-----BEGIN ")
+        .trim_end_matches("-----");
+
+    rest.contains(&format!("-----END {}-----", label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Option<String> {
+        SensitiveDetector::new(&[]).detect(text)
+    }
+
+    #[test]
+    fn detects_card_numbers_of_known_brands() {
+        for number in [
+            "4111 1111 1111 1111",
+            "5555-5555-5555-4444",
+            "2223003122003222",
+            "378282246310005",
+            "6011111111111117",
+        ] {
+            assert_eq!(detect(number).as_deref(), Some("credit_card"), "{}", number);
+        }
+    }
+
+    #[test]
+    fn ignores_epoch_millis() {
+        // Luhnチェックサムは正しいが、発行者番号に一致しない
+        assert!(luhn_valid("1760000000008"));
+        assert_eq!(detect("created_at: 1760000000008"), None);
+    }
+
+    #[test]
+    fn ignores_long_numeric_ids() {
+        assert!(luhn_valid("1234567890123456785"));
+        assert_eq!(detect("order 1234567890123456785"), None);
+        assert!(luhn_valid("9000000000000001"));
+        assert_eq!(detect("tracking 9000000000000001"), None);
+    }
+
+    #[test]
+    fn rejects_known_prefix_with_wrong_length() {
+        // Amexは15桁のみ
+        assert!(!card_number_valid("3782822463100005"));
+    }
+}
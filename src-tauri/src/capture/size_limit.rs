@@ -0,0 +1,143 @@
+use crate::capture::formats;
+use crate::database::{truncate_to_char_boundary, NewClipboardItem};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// サイズ上限を超えた形式の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizePolicy {
+    /// 保存しない（プライマリ形式の場合はアイテムごと保存しない）
+    Skip,
+    /// 上限までに切り詰め、末尾に切り詰めた旨を追記（バイナリ形式は保存しない）
+    Truncate,
+    /// 全体をBLOBファイルに退避し、一覧には先頭部分のみ返す
+    Offload,
+}
+
+/// 形式ごとのサイズ上限（上から順に最初に一致したものを使う）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeLimit {
+    pub format: String, // 形式（末尾の*で前方一致）
+    pub max_kb: u64,
+    pub policy: OversizePolicy,
+}
+
+/// 既定のサイズ上限
+pub fn default_size_limits() -> Vec<SizeLimit> {
+    vec![
+        SizeLimit {
+            format: "text/*".to_string(),
+            max_kb: 10 * 1024,
+            policy: OversizePolicy::Truncate,
+        },
+        SizeLimit {
+            format: "image/*".to_string(),
+            max_kb: 64 * 1024,
+            policy: OversizePolicy::Offload,
+        },
+        SizeLimit {
+            format: "*".to_string(),
+            max_kb: 32 * 1024,
+            policy: OversizePolicy::Skip,
+        },
+    ]
+}
+
+/// サイズ上限の設定を検証
+pub fn validate_limits(limits: &[SizeLimit]) -> Result<(), String> {
+    for limit in limits {
+        if limit.format.trim().is_empty() {
+            return Err("サイズ上限の形式が空です".to_string());
+        }
+        if limit.max_kb == 0 {
+            return Err(format!(
+                "サイズ上限は1KB以上である必要があります: {}",
+                limit.format
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 各形式にサイズ上限を適用（プライマリ形式を保存できなくなった場合はfalse）
+pub fn enforce(item: &mut NewClipboardItem, limits: &[SizeLimit]) -> bool {
+    let text_formats: Vec<String> = item.format_contents.keys().cloned().collect();
+    for format in text_formats {
+        let Some(limit) = find_limit(limits, &format) else {
+            continue;
+        };
+        let max_bytes = limit.max_kb as usize * 1024;
+        let size = item.format_contents[&format].len();
+        if size <= max_bytes {
+            continue;
+        }
+
+        warn!(
+            "サイズ上限を超過: {} ({}KB > {}KB, {:?})",
+            format,
+            size / 1024,
+            limit.max_kb,
+            limit.policy
+        );
+        match limit.policy {
+            OversizePolicy::Skip => {
+                item.format_contents.remove(&format);
+            }
+            OversizePolicy::Truncate => {
+                let text = item.format_contents.get_mut(&format).unwrap();
+                let marker = truncation_marker(size);
+                let kept = truncate_to_char_boundary(text, max_bytes.saturating_sub(marker.len()));
+                *text = format!("{}{}", kept, marker);
+                item.truncated_formats.insert(format, size);
+            }
+            OversizePolicy::Offload => {
+                item.offloaded_formats.insert(format);
+            }
+        }
+    }
+
+    let binary_formats: Vec<String> = item.binary_contents.keys().cloned().collect();
+    for format in binary_formats {
+        let Some(limit) = find_limit(limits, &format) else {
+            continue;
+        };
+        let size = item.binary_contents[&format].len();
+        if size <= limit.max_kb as usize * 1024 {
+            continue;
+        }
+
+        warn!(
+            "サイズ上限を超過: {} ({}KB > {}KB, {:?})",
+            format,
+            size / 1024,
+            limit.max_kb,
+            limit.policy
+        );
+        // バイナリ形式は途中で切ると使えないため、切り詰めの場合も保存しない
+        if limit.policy == OversizePolicy::Offload {
+            item.offloaded_formats.insert(format);
+        } else {
+            item.binary_contents.remove(&format);
+            item.images.remove(&format);
+        }
+    }
+
+    item.format_contents.contains_key(&item.primary_format)
+        || item.binary_contents.contains_key(&item.primary_format)
+}
+
+/// 形式に適用するサイズ上限
+fn find_limit<'a>(limits: &'a [SizeLimit], format: &str) -> Option<&'a SizeLimit> {
+    limits
+        .iter()
+        .find(|limit| formats::matches_pattern(&limit.format, format))
+}
+
+/// 切り詰めたテキストの末尾に付ける目印
+fn truncation_marker(original_size: usize) -> String {
+    format!(
+        "\n\n[… 切り詰められました（元のサイズ: {:.1}MB）]",
+        original_size as f64 / (1024.0 * 1024.0)
+    )
+}
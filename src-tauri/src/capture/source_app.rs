@@ -0,0 +1,201 @@
+use crate::database::{ClipboardSelection, SourceApp};
+
+/// コピー元アプリケーションを検出（検出できない場合は不明として空の情報を返す）
+pub fn detect(selection: ClipboardSelection) -> SourceApp {
+    #[cfg(target_os = "linux")]
+    {
+        match x11::detect(selection) {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::warn!("コピー元アプリの検出に失敗: {}", e);
+                SourceApp::default()
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = selection;
+        SourceApp::default()
+    }
+}
+
+/// /proc からプロセスの実行ファイルとプロセス名を取得
+#[cfg(target_os = "linux")]
+fn process_info(pid: u32) -> (Option<String>, Option<String>) {
+    let executable = std::fs::read_link(format!("/proc/{}/exe", pid))
+        .ok()
+        .map(|path| path.display().to_string());
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    (executable, comm)
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::process_info;
+    use crate::database::{ClipboardSelection, SourceApp};
+    use anyhow::Result;
+    use std::sync::{Mutex, PoisonError};
+    use tracing::debug;
+    use x11rb::connection::Connection;
+    use x11rb::errors::ReplyError;
+    use x11rb::properties::WmClass;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, Window};
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::NONE;
+
+    x11rb::atom_manager! {
+        Atoms: AtomsCookie {
+            CLIPBOARD,
+            UTF8_STRING,
+            _NET_ACTIVE_WINDOW,
+            _NET_WM_PID,
+            _NET_WM_NAME,
+        }
+    }
+
+    /// Xサーバーへの接続とルートウィンドウ・アトム
+    struct Session {
+        conn: RustConnection,
+        root: Window,
+        atoms: Atoms,
+    }
+
+    impl Session {
+        fn connect() -> Result<Self> {
+            let (conn, screen_num) = x11rb::connect(None)?;
+            let root = conn.setup().roots[screen_num].root;
+            let atoms = Atoms::new(&conn)?.reply()?;
+            Ok(Self { conn, root, atoms })
+        }
+    }
+
+    // クリップボードの変更ごとに接続し直さないよう保持する（エラー時のみ再接続）
+    static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+    /// セレクションの所有者ウィンドウ、見つからなければアクティブウィンドウから検出
+    pub fn detect(selection: ClipboardSelection) -> Result<SourceApp> {
+        let mut session = SESSION.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // 保持していた接続が切れていた場合（Xサーバーの再起動など）は接続し直して再試行
+        if let Some(current) = session.take() {
+            match detect_with(&current, selection) {
+                Ok(source) => {
+                    *session = Some(current);
+                    return Ok(source);
+                }
+                Err(e) => debug!("X接続を再接続します: {}", e),
+            }
+        }
+
+        let current = Session::connect()?;
+        let source = detect_with(&current, selection)?;
+        *session = Some(current);
+        Ok(source)
+    }
+
+    fn detect_with(session: &Session, selection: ClipboardSelection) -> Result<SourceApp> {
+        let Session { conn, root, atoms } = session;
+
+        // 所有者ウィンドウは非表示のヘルパーウィンドウでWM_CLASSを持たない場合がある
+        let selection = match selection {
+            ClipboardSelection::Clipboard => atoms.CLIPBOARD,
+            ClipboardSelection::Primary => AtomEnum::PRIMARY.into(),
+        };
+        let owner = conn.get_selection_owner(selection)?.reply()?.owner;
+        let active = conn
+            .get_property(
+                false,
+                *root,
+                atoms._NET_ACTIVE_WINDOW,
+                AtomEnum::WINDOW,
+                0,
+                1,
+            )?
+            .reply()?
+            .value32()
+            .and_then(|mut values| values.next())
+            .unwrap_or(NONE);
+
+        for window in [owner, active] {
+            if window == NONE {
+                continue;
+            }
+
+            // ウィンドウが既に閉じられているなどのエラーは不明として次の候補を調べる
+            match describe_window(conn, atoms, window) {
+                Ok(source) if source.name.is_some() => return Ok(source),
+                Ok(_) => {}
+                Err(e) if is_protocol_error(&e) => {
+                    debug!("ウィンドウ情報の取得に失敗: {:#x} ({})", window, e)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(SourceApp::default())
+    }
+
+    /// ウィンドウに対する要求のエラーか（接続自体のエラーでなければ他のウィンドウは調べられる）
+    fn is_protocol_error(error: &anyhow::Error) -> bool {
+        matches!(
+            error.downcast_ref::<ReplyError>(),
+            Some(ReplyError::X11Error(_))
+        )
+    }
+
+    /// ウィンドウのWM_CLASS・PID・タイトルからアプリ情報を組み立てる
+    fn describe_window(conn: &RustConnection, atoms: &Atoms, window: Window) -> Result<SourceApp> {
+        let class = WmClass::get(conn, window)?
+            .reply()
+            .ok()
+            .flatten()
+            .map(|class| {
+                // クラス名（例: firefox）を優先し、なければインスタンス名
+                let name = if class.class().is_empty() {
+                    class.instance()
+                } else {
+                    class.class()
+                };
+                String::from_utf8_lossy(name).into_owned()
+            });
+
+        let pid = conn
+            .get_property(false, window, atoms._NET_WM_PID, AtomEnum::CARDINAL, 0, 1)?
+            .reply()?
+            .value32()
+            .and_then(|mut values| values.next());
+        let (executable, comm) = pid.map(process_info).unwrap_or_default();
+
+        Ok(SourceApp {
+            name: class.filter(|name| !name.is_empty()).or(comm),
+            executable,
+            window_title: window_title(conn, atoms, window)?,
+        })
+    }
+
+    /// ウィンドウタイトル（_NET_WM_NAME、なければWM_NAME）
+    fn window_title(
+        conn: &RustConnection,
+        atoms: &Atoms,
+        window: Window,
+    ) -> Result<Option<String>> {
+        for (property, type_) in [
+            (atoms._NET_WM_NAME, atoms.UTF8_STRING),
+            (AtomEnum::WM_NAME.into(), AtomEnum::ANY.into()),
+        ] {
+            let reply = conn
+                .get_property(false, window, property, type_, 0, 1024)?
+                .reply()?;
+            if !reply.value.is_empty() {
+                return Ok(Some(String::from_utf8_lossy(&reply.value).into_owned()));
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,100 @@
+use crate::capture::app_filter::{AppCaptureRule, AppRuleAction};
+use crate::commands::settings::{
+    delete_rule, modify_settings, reorder_rules, upsert_rule, AppSettings, SettingsRule,
+};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// アプリ別キャプチャルールの一覧と既定の動作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppCaptureRules {
+    pub default_action: AppRuleAction,
+    pub rules: Vec<AppCaptureRule>,
+}
+
+impl SettingsRule for AppCaptureRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut String {
+        &mut self.id
+    }
+}
+
+impl From<AppSettings> for AppCaptureRules {
+    fn from(settings: AppSettings) -> Self {
+        Self {
+            default_action: settings.app_capture_default_action,
+            rules: settings.app_capture_rules,
+        }
+    }
+}
+
+/// アプリ別キャプチャルールを取得
+#[tauri::command]
+pub async fn get_app_capture_rules() -> Result<AppCaptureRules, String> {
+    let settings = AppSettings::load()
+        .await
+        .map_err(|e| format!("設定読み込みエラー: {}", e))?;
+
+    Ok(settings.into())
+}
+
+/// アプリ別キャプチャルールを追加・更新（IDが空または未登録の場合は末尾に追加）
+#[tauri::command]
+pub async fn save_app_capture_rule(
+    app: AppHandle,
+    rule: AppCaptureRule,
+) -> Result<AppCaptureRules, String> {
+    rule.validate()?;
+
+    modify_settings(&app, "app_capture_rules", |settings| {
+        upsert_rule(&mut settings.app_capture_rules, rule);
+        Ok(())
+    })
+    .await
+    .map(AppCaptureRules::from)
+}
+
+/// アプリ別キャプチャルールを削除
+#[tauri::command]
+pub async fn delete_app_capture_rule(
+    app: AppHandle,
+    id: String,
+) -> Result<AppCaptureRules, String> {
+    modify_settings(&app, "app_capture_rules", |settings| {
+        delete_rule(&mut settings.app_capture_rules, &id);
+        Ok(())
+    })
+    .await
+    .map(AppCaptureRules::from)
+}
+
+/// アプリ別キャプチャルールの評価順を並べ替え（指定されなかったルールは末尾に残す）
+#[tauri::command]
+pub async fn reorder_app_capture_rules(
+    app: AppHandle,
+    ids: Vec<String>,
+) -> Result<AppCaptureRules, String> {
+    modify_settings(&app, "app_capture_rules", |settings| {
+        reorder_rules(&mut settings.app_capture_rules, &ids);
+        Ok(())
+    })
+    .await
+    .map(AppCaptureRules::from)
+}
+
+/// どのルールにも一致しない場合の動作を設定
+#[tauri::command]
+pub async fn set_app_capture_default_action(
+    app: AppHandle,
+    action: AppRuleAction,
+) -> Result<AppCaptureRules, String> {
+    modify_settings(&app, "app_capture_default_action", |settings| {
+        settings.app_capture_default_action = action;
+        Ok(())
+    })
+    .await
+    .map(AppCaptureRules::from)
+}
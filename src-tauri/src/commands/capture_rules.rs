@@ -0,0 +1,123 @@
+use crate::capture::classifier::ContentKind;
+use crate::capture::rules::{CaptureRule, RuleOutcome, RuleSet};
+use crate::commands::settings::{
+    delete_rule, modify_settings, reorder_rules, upsert_rule, AppSettings, SettingsRule,
+};
+use crate::database::{NewClipboardItem, SourceApp};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+/// キャプチャルールの試行結果
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureRuleTestResult {
+    pub outcome: RuleOutcome,
+    pub kind: Option<ContentKind>, // 評価後のテキストの内容の種類
+    pub text: String,              // 変換を適用した後のテキスト
+}
+
+impl SettingsRule for CaptureRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut String {
+        &mut self.id
+    }
+}
+
+/// 設定を読み込む
+async fn load_settings() -> Result<AppSettings, String> {
+    AppSettings::load()
+        .await
+        .map_err(|e| format!("設定読み込みエラー: {}", e))
+}
+
+/// キャプチャルールを取得
+#[tauri::command]
+pub async fn get_capture_rules() -> Result<Vec<CaptureRule>, String> {
+    Ok(load_settings().await?.capture_rules)
+}
+
+/// キャプチャルールを追加・更新（IDが空または未登録の場合は末尾に追加）
+#[tauri::command]
+pub async fn save_capture_rule(
+    app: AppHandle,
+    rule: CaptureRule,
+) -> Result<Vec<CaptureRule>, String> {
+    rule.validate()?;
+
+    let settings = modify_settings(&app, "capture_rules", |settings| {
+        upsert_rule(&mut settings.capture_rules, rule);
+        Ok(())
+    })
+    .await?;
+    Ok(settings.capture_rules)
+}
+
+/// キャプチャルールを削除
+#[tauri::command]
+pub async fn delete_capture_rule(app: AppHandle, id: String) -> Result<Vec<CaptureRule>, String> {
+    let settings = modify_settings(&app, "capture_rules", |settings| {
+        delete_rule(&mut settings.capture_rules, &id);
+        Ok(())
+    })
+    .await?;
+    Ok(settings.capture_rules)
+}
+
+/// キャプチャルールの評価順を並べ替え（指定されなかったルールは末尾に残す）
+#[tauri::command]
+pub async fn reorder_capture_rules(
+    app: AppHandle,
+    ids: Vec<String>,
+) -> Result<Vec<CaptureRule>, String> {
+    let settings = modify_settings(&app, "capture_rules", |settings| {
+        reorder_rules(&mut settings.capture_rules, &ids);
+        Ok(())
+    })
+    .await?;
+    Ok(settings.capture_rules)
+}
+
+/// テキストに対してキャプチャルールを試行（保存はしない）
+///
+/// ルールを指定した場合はそのルールのみ（無効でも評価）、省略した場合は保存済みの全ルールを評価する。
+#[tauri::command]
+pub async fn test_capture_rule(
+    text: String,
+    rule: Option<CaptureRule>,
+    source_app: Option<String>,
+) -> Result<CaptureRuleTestResult, String> {
+    let rules = match rule {
+        Some(rule) => {
+            rule.validate()?;
+            vec![CaptureRule {
+                enabled: true,
+                ..rule
+            }]
+        }
+        None => load_settings().await?.capture_rules,
+    };
+
+    let mut item = NewClipboardItem {
+        primary_format: "text/plain".to_string(),
+        format_contents: HashMap::from([("text/plain".to_string(), text)]),
+        source: SourceApp {
+            name: source_app,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    item.classify();
+
+    let outcome = RuleSet::new(&rules).evaluate(&mut item);
+    Ok(CaptureRuleTestResult {
+        outcome,
+        kind: item.classification.map(|c| c.kind),
+        text: item
+            .format_contents
+            .remove("text/plain")
+            .unwrap_or_default(),
+    })
+}
@@ -1,388 +1,551 @@
-use crate::database::{Database, DisplayClipboardItem};
-use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use crate::capture::app_filter::AppRuleAction;
+use crate::capture::health::{self, WatcherHealth};
+use crate::capture::lifecycle;
+use crate::capture::pause::{self, PauseDuration, PauseStatus};
+use crate::capture::poller::{PollingShutdown, PollingWatcher};
+use crate::capture::primary;
+use crate::capture::{
+    self, classifier, concealed, formats, imaging, own_writes, source_app, CaptureEvent,
+    CaptureSender,
+};
+use crate::commands::settings::{AppSettings, WatcherBackend};
+use crate::database::{hash, ClipboardSelection, Database, DisplayClipboardItem, NewClipboardItem};
+use crate::events::{self, AppEvent};
 use clipboard_rs::{
-    common::RustImage, Clipboard, ClipboardContext, ClipboardHandler, ClipboardWatcher,
-    ClipboardWatcherContext, ContentFormat,
+    Clipboard, ClipboardContext, ClipboardHandler, ClipboardWatcher, ClipboardWatcherContext,
+    ContentFormat,
 };
-use std::sync::atomic::{AtomicBool, Ordering};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, State};
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+use tracing::{debug, error, info, warn};
 
 // グローバルな監視状態
 static MONITORING: AtomicBool = AtomicBool::new(false);
 
+// 監視の開始・停止のたびに進める世代番号（古いウォッチャーの再起動を止める）
+static WATCHER_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// ウォッチャーの再起動までの待ち時間の初期値と上限（失敗が続くたびに倍にする）
+pub const RESTART_BACKOFF_INITIAL: Duration = Duration::from_secs(1);
+pub const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// これ以上動作してから終了した場合は待ち時間を初期値に戻す
+pub const HEALTHY_RUN: Duration = Duration::from_secs(60);
+
 // shutdown_channelをグローバルで保持
 use clipboard_rs::WatcherShutdown;
 use std::sync::Mutex as StdMutex;
-static SHUTDOWN_CHANNEL: StdMutex<Option<WatcherShutdown>> = StdMutex::new(None);
+static SHUTDOWN_CHANNEL: StdMutex<Option<WatcherStop>> = StdMutex::new(None);
+
+/// 停止ハンドルを取得（ウォッチャーのスレッドがパニックしてロックが汚染されていても続行する）
+fn lock_shutdown_channel() -> std::sync::MutexGuard<'static, Option<WatcherStop>> {
+    SHUTDOWN_CHANNEL
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// ウォッチャーのイベントループを実行する関数（停止要求まで戻らない）
+type StartWatch = Box<dyn FnOnce() + Send>;
+
+/// 動作中のウォッチャーの停止ハンドル
+enum WatcherStop {
+    Event(WatcherShutdown),
+    Polling(PollingShutdown),
+}
+
+impl WatcherStop {
+    fn stop(self) {
+        match self {
+            WatcherStop::Event(shutdown) => shutdown.stop(),
+            WatcherStop::Polling(shutdown) => shutdown.stop(),
+        }
+    }
+
+    /// 実際に使われている監視方式
+    fn backend(&self) -> WatcherBackend {
+        match self {
+            WatcherStop::Event(_) => WatcherBackend::Event,
+            WatcherStop::Polling(_) => WatcherBackend::Polling,
+        }
+    }
+}
 
 // クリップボード監視ハンドラー
 pub struct ClipboardManager {
-    app: AppHandle,
-    db: Arc<Mutex<Database>>,
-    last_content: String,
+    capture: CaptureSender,
+    last_content_hash: String,
 }
 
 impl ClipboardManager {
-    pub fn new(app: AppHandle, db: Arc<Mutex<Database>>) -> Self {
-        let ctx = ClipboardContext::new().unwrap();
+    pub fn new(capture: CaptureSender) -> Result<Self, String> {
+        let ctx = ClipboardContext::new()
+            .map_err(|e| format!("クリップボードコンテキスト作成エラー: {}", e))?;
         let last_content = ctx.get_text().unwrap_or_default();
 
-        ClipboardManager {
-            app,
-            db,
-            last_content,
-        }
+        Ok(ClipboardManager {
+            capture,
+            last_content_hash: hash::content_hash(&last_content, false),
+        })
     }
 }
 
 impl ClipboardHandler for ClipboardManager {
     fn on_clipboard_change(&mut self) {
-        println!("🎉 clipboard-rs: クリップボード変更検出!");
+        debug!("clipboard-rs: クリップボード変更検出!");
+
+        // 一時停止中は内容を読み取らない
+        if pause::is_paused() {
+            info!("一時停止中のため記録しません");
+            return;
+        }
 
         // 新しい内容を取得 - ClipboardHandlerでは毎回新しいcontextを作る必要がある
         let ctx = match ClipboardContext::new() {
             Ok(ctx) => ctx,
             Err(e) => {
-                eprintln!("❌ ClipboardContext作成エラー: {}", e);
+                error!("ClipboardContext作成エラー: {}", e);
                 return;
             }
         };
 
-        // 利用可能な形式を検出
-        let available_formats = detect_clipboard_formats(&ctx);
-        println!("🔍 検出された形式: {:?}", available_formats);
+        // コピー元アプリを検出（コピー直後のクリップボード所有者・アクティブウィンドウ）
+        let source = source_app::detect(ClipboardSelection::Clipboard);
+        debug!("コピー元: {:?}", source.name);
 
-        // 全ての利用可能な形式を収集
-        let all_format_contents = collect_all_format_contents(&ctx);
+        // 内容を読み取る前にアプリ別キャプチャルールを適用
+        let settings = tauri::async_runtime::block_on(AppSettings::cached()).unwrap_or_default();
+        let matchers = tauri::async_runtime::block_on(AppSettings::matchers());
+        if matchers.app_filter.evaluate(&source) == AppRuleAction::Block {
+            info!("キャプチャルールにより記録しません: {:?}", source.name);
+            return;
+        }
 
-        // 利用可能な形式のリストを作成
-        let available_formats: Vec<String> = all_format_contents.keys().cloned().collect();
+        // 内容を読み取る前にパスワードマネージャーの機密ヒントを確認
+        let targets = ctx.available_formats().unwrap_or_default();
+        if let Some(hint) = concealed::find_hint(&ctx, &targets) {
+            info!("機密ヒントを検出したため内容を読み取りません: {}", hint);
+            // 同じ内容が再度コピーされても検出できるよう直前のハッシュをリセット
+            self.last_content_hash.clear();
+            self.capture.enqueue(CaptureEvent {
+                item: NewClipboardItem {
+                    source,
+                    ..Default::default()
+                },
+                concealed: true,
+            });
+            return;
+        }
 
-        // 優先順位に従ってコンテンツを取得
-        let (current_content, detected_format) = match get_clipboard_content_by_priority(&ctx) {
-            Ok(content_info) => content_info,
-            Err(e) => {
-                eprintln!("❌ クリップボード読み取りエラー: {}", e);
-                // フォールバック: 基本テキスト取得を試行
-                match ctx.get_text() {
-                    Ok(text) => {
-                        println!("⚠️ フォールバック: テキストとして取得");
-                        (text, "text/plain".to_string())
-                    }
-                    Err(text_err) => {
-                        eprintln!("❌ フォールバック失敗: {}", text_err);
-                        return;
-                    }
-                }
-            }
+        // 利用可能な形式を検出
+        let available_formats = formats::available(
+            &ctx,
+            &targets,
+            &settings.extra_capture_formats,
+            settings.derive_markdown_from_html,
+        );
+        debug!("検出された形式: {:?}", available_formats);
+
+        // 全ての利用可能な形式を収集（優先順位に従ってプライマリ形式も決定）
+        let Some(mut event) =
+            collect_all_format_contents(&ctx, &targets, &settings.extra_capture_formats)
+        else {
+            error!("利用可能なクリップボード形式がありません");
+            return;
         };
 
         // 重複チェック
-        if current_content == self.last_content || current_content.is_empty() {
+        let content_hash = event.item.content_hash(false);
+        if content_hash == self.last_content_hash {
             return;
         }
 
-        // UTF-8文字境界を考慮した安全なスライス
-        let preview = if current_content.len() <= 100 {
-            current_content.as_str()
-        } else {
-            // 100バイト以下で有効な文字境界を見つける
-            let mut boundary = 100;
-            while boundary > 0 && !current_content.is_char_boundary(boundary) {
-                boundary -= 1;
-            }
-            &current_content[..boundary]
-        };
+        debug!(
+            "新しい内容 ({}): {}",
+            event.item.primary_format,
+            event.item.log_summary()
+        );
+        self.last_content_hash = content_hash;
+        event.item.source = source;
 
-        println!("📝 新しい内容: {}", preview);
-        self.last_content = current_content.clone();
-
-        // 非同期でデータベース処理を実行
-        let app_clone = self.app.clone();
-        let db_clone = Arc::clone(&self.db);
-        let content_clone = current_content.clone();
-        let format_clone = detected_format.clone();
-        let formats_clone = available_formats.clone();
-        let contents_clone = all_format_contents.clone();
-
-        // 新しいランタイムで非同期処理を実行
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async move {
-                let db = db_clone.lock().await;
-
-                // 重複チェック - より厳密に
-                let recent_items = match db.get_history(Some(5), None).await {
-                    Ok(items) => items,
-                    Err(e) => {
-                        eprintln!("❌ 履歴取得エラー: {}", e);
-                        return;
-                    }
-                };
-
-                // 直近5件の中に同一内容があるかチェック（コピーボタン対策）
-                let is_duplicate = recent_items.iter().any(|item| {
-                    // プライマリコンテンツと同じかチェック
-                    if let Some(primary_content) = item
-                        .contents
-                        .iter()
-                        .find(|c| c.format == item.primary_format)
-                    {
-                        primary_content.content == content_clone
-                    } else {
-                        // プライマリが見つからない場合は任意のコンテンツと比較
-                        item.contents
-                            .iter()
-                            .any(|content| content.content == content_clone)
-                    }
-                });
-
-                // さらに、直前のアイテムと完全に同一の場合は確実にスキップ
-                if let Some(latest_item) = recent_items.first() {
-                    if let Some(latest_content) = latest_item
-                        .contents
-                        .iter()
-                        .find(|c| c.format == latest_item.primary_format)
-                    {
-                        if latest_content.content == content_clone
-                            && latest_item.primary_format == format_clone
-                        {
-                            // UTF-8文字境界を考慮した安全なスライス
-                            let preview = if content_clone.len() <= 50 {
-                                content_clone.as_str()
-                            } else {
-                                // 50バイト以下で有効な文字境界を見つける
-                                let mut boundary = 50;
-                                while boundary > 0 && !content_clone.is_char_boundary(boundary) {
-                                    boundary -= 1;
-                                }
-                                &content_clone[..boundary]
-                            };
-                            println!(
-                                "🔄 直前と同一の内容・フォーマットのため重複スキップ: {}",
-                                preview
-                            );
-                            return;
-                        }
-                    }
-                }
-
-                if !is_duplicate {
-                    match db
-                        .save_clipboard_item_with_all_formats(
-                            &content_clone,
-                            &format_clone,
-                            Some("clipboard-rs"),
-                            &formats_clone,
-                            &format_clone,
-                            &contents_clone,
-                        )
-                        .await
-                    {
-                        Ok(saved_item) => {
-                            println!("✅ データベース保存完了 ID: {}", saved_item.id);
-
-                            // フロントエンドにイベント通知
-                            if let Err(e) = app_clone.emit("clipboard-updated", &saved_item) {
-                                eprintln!("❌ イベント送信エラー: {}", e);
-                            } else {
-                                println!("📤 フロントエンドにイベント通知成功");
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("❌ データベース保存エラー: {}", e);
-                        }
-                    }
-                } else {
-                    println!("⚠️ 重複のため保存スキップ");
-                }
-            });
-        });
+        // 保存処理は常駐キャプチャワーカーに順番に任せる
+        self.capture.enqueue(event);
     }
 }
 
 /// クリップボードアイテムをデータベースに保存
 #[tauri::command]
 pub async fn save_clipboard_item(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
     content: String,
     content_type: Option<String>,
     source_app: Option<String>,
 ) -> Result<DisplayClipboardItem, String> {
-    let db = db_state.lock().await;
-    let content_type = content_type.unwrap_or_else(|| {
-        // コンテンツタイプを自動判定
-        if content.starts_with("http://") || content.starts_with("https://") {
-            "text/uri-list".to_string()
-        } else if content.starts_with("data:image/") {
-            "image/png".to_string() // デフォルトでPNG、実際のMIMEタイプは後で解析
-        } else if content.starts_with("data:") {
-            "application/octet-stream".to_string()
-        } else if content.contains("<html") || content.contains("</html>") {
-            "text/html".to_string()
-        } else if content.starts_with("{\\rtf") {
-            "text/rtf".to_string()
-        } else if content.contains("\n") && content.len() > 100 {
-            "text/plain".to_string() // 長い複数行テキスト
-        } else if content.starts_with("/") || content.starts_with("C:\\") || content.contains("\\")
-        {
-            "files".to_string() // ファイルパス
-        } else {
-            "text/plain".to_string()
-        }
-    });
+    // 形式の指定がなければ内容の分類結果から決める（data URLはそのMIMEタイプ）
+    let content_type = content_type.unwrap_or_else(|| classifier::classify(&content).mime_type());
 
-    db.save_clipboard_item(&content, &content_type, source_app.as_deref())
+    let item = db
+        .save_clipboard_item(&content, &content_type, source_app.as_deref())
         .await
-        .map_err(|e| format!("データベース保存エラー: {}", e))
+        .map_err(|e| format!("データベース保存エラー: {}", e))?;
+
+    events::item_added(&app, &item.id);
+    Ok(item)
 }
 
 /// 重複チェック（同じコンテンツが既に存在するかチェック）
 #[tauri::command]
 pub async fn check_duplicate_content(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    db: State<'_, Arc<Database>>,
     content: String,
 ) -> Result<bool, String> {
-    let db = db_state.lock().await;
+    let settings = AppSettings::load().await.unwrap_or_default();
 
-    // 最近の10件をチェックして重複を確認
-    let recent_items = db
-        .get_history(Some(10), None)
+    // コンテンツハッシュのインデックスで重複を確認
+    let existing_id = db
+        .find_item_id_by_content(&content, settings.ignore_whitespace_duplicates)
         .await
-        .map_err(|e| format!("履歴取得エラー: {}", e))?;
+        .map_err(|e| format!("重複チェックエラー: {}", e))?;
 
-    // 正規化されたデータベースでの重複チェック
-    let is_duplicate = recent_items.iter().any(|item| {
-        item.contents
-            .iter()
-            .any(|content_item| content_item.content == content)
-    });
-    Ok(is_duplicate)
+    Ok(existing_id.is_some())
 }
 
-/// clipboard-rsでクリップボード監視を開始
+/// clipboard-rsでクリップボード監視を開始（ユーザー操作）
 #[tauri::command]
 pub async fn start_clipboard_monitoring(
     app: AppHandle,
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    db: State<'_, Arc<Database>>,
 ) -> Result<(), String> {
+    start_monitoring(app, Arc::clone(&db)).await?;
+    lifecycle::set_stopped(false);
+    Ok(())
+}
+
+/// clipboard-rsでクリップボード監視を開始（起動時はバックエンドから直接呼ぶ）
+pub async fn start_monitoring(app: AppHandle, db: Arc<Database>) -> Result<(), String> {
     if MONITORING.load(Ordering::Relaxed) {
-        println!("⚠️ 既に監視中です");
+        warn!("既に監視中です");
         return Ok(()); // 既に監視中
     }
 
-    println!("🚀 clipboard-rs でクリップボード監視を開始...");
+    info!("clipboard-rs でクリップボード監視を開始...");
 
     // まず現在のクリップボード内容をテスト
     match ClipboardContext::new() {
         Ok(ctx) => match ctx.get_text() {
-            Ok(text) => {
-                // UTF-8文字境界を考慮した安全なスライス
-                let preview = if text.len() <= 50 {
-                    text.as_str()
-                } else {
-                    // 50バイト以下で有効な文字境界を見つける
-                    let mut boundary = 50;
-                    while boundary > 0 && !text.is_char_boundary(boundary) {
-                        boundary -= 1;
-                    }
-                    &text[..boundary]
-                };
-                println!("📋 現在のクリップボード内容: {}", preview);
-            }
-            Err(e) => println!("❌ クリップボード読み取りテストエラー: {}", e),
+            Ok(text) => debug!(
+                "現在のクリップボード内容: {}",
+                hash::log_summary(text.len(), &hash::content_hash(&text, false))
+            ),
+            Err(e) => error!("クリップボード読み取りテストエラー: {}", e),
         },
         Err(e) => {
             return Err(format!("クリップボードコンテキスト作成エラー: {}", e));
         }
     }
 
-    // ウォッチャーを作成
-    let mut watcher: ClipboardWatcherContext<ClipboardManager> =
-        match ClipboardWatcherContext::new() {
-            Ok(watcher) => {
-                println!("✅ ClipboardWatcherContext作成成功");
-                watcher
-            }
+    // ウォッチャーを作成（設定に応じて、または作成に失敗した場合はポーリングに切り替え）
+    let settings = AppSettings::load().await.unwrap_or_default();
+    let event_watcher = open_event_watcher(settings.watcher_backend)?;
+
+    // 常駐キャプチャワーカーを起動
+    let capture = capture::start_worker(app.clone(), db);
+
+    // 設定で有効な場合はPRIMARYセレクションも別スレッドで監視
+    if settings.primary_selection_enabled {
+        primary::start(capture.clone(), settings.primary_selection_debounce_ms);
+    }
+
+    let (shutdown_channel, start_watch) =
+        match attach_handler(event_watcher, capture.clone(), settings.polling_interval_ms) {
+            Ok(attached) => attached,
             Err(e) => {
-                let error_msg = format!("ウォッチャー作成エラー: {}", e);
-                println!("❌ {}", error_msg);
-                return Err(error_msg);
+                primary::stop();
+                capture::stop_worker().await;
+                return Err(e);
             }
         };
 
-    // db_stateをクローンしてスレッドに渡す
-    let db_arc = Arc::clone(&db_state);
+    // shutdown_channelをグローバルに保存し、この監視の世代を進める
+    let generation = {
+        let mut global_shutdown = lock_shutdown_channel();
+        *global_shutdown = Some(shutdown_channel);
+        WATCHER_GENERATION.fetch_add(1, Ordering::Relaxed) + 1
+    };
 
-    // クリップボードマネージャーを作成
-    let manager = ClipboardManager::new(app.clone(), Arc::clone(&db_arc));
-    println!("✅ ClipboardManager作成成功");
+    MONITORING.store(true, Ordering::Relaxed);
+    emit_monitoring_state(&app);
 
-    // ハンドラーを追加
-    let shutdown_channel = watcher.add_handler(manager).get_shutdown_channel();
-    println!("✅ ハンドラー追加成功");
+    // clipboard-rsのstart_watch()は**ブロッキング**実行なので別スレッドで実行
+    std::thread::spawn(move || supervise(app, capture, generation, start_watch));
 
-    // shutdown_channelをグローバルに保存
-    {
-        let mut global_shutdown = SHUTDOWN_CHANNEL.lock().unwrap();
-        *global_shutdown = Some(shutdown_channel);
+    info!("clipboard-rs 監視がバックグラウンドで開始されました");
+    Ok(())
+}
+
+/// 変更イベントを受け取るウォッチャーを作成（ポーリングを使う場合はNone）
+fn open_event_watcher(
+    backend: WatcherBackend,
+) -> Result<Option<ClipboardWatcherContext<ClipboardManager>>, String> {
+    match backend {
+        WatcherBackend::Polling => Ok(None),
+        backend => match ClipboardWatcherContext::<ClipboardManager>::new() {
+            Ok(watcher) => {
+                info!("ClipboardWatcherContext作成成功");
+                Ok(Some(watcher))
+            }
+            Err(e) if backend == WatcherBackend::Auto => {
+                warn!(
+                    "ウォッチャー作成エラーのためポーリング監視に切り替えます: {}",
+                    e
+                );
+                Ok(None)
+            }
+            Err(e) => {
+                let error_msg = format!("ウォッチャー作成エラー: {}", e);
+                error!("{}", error_msg);
+                Err(error_msg)
+            }
+        },
     }
+}
 
-    MONITORING.store(true, Ordering::Relaxed);
+/// ウォッチャーにハンドラーを追加し、停止ハンドルと監視を開始する関数を返す
+fn attach_handler(
+    event_watcher: Option<ClipboardWatcherContext<ClipboardManager>>,
+    capture: CaptureSender,
+    polling_interval_ms: u64,
+) -> Result<(WatcherStop, StartWatch), String> {
+    let manager = ClipboardManager::new(capture).inspect_err(|e| error!("{}", e))?;
+
+    Ok(match event_watcher {
+        Some(mut watcher) => {
+            let shutdown = watcher.add_handler(manager).get_shutdown_channel();
+            (
+                WatcherStop::Event(shutdown),
+                Box::new(move || watcher.start_watch()),
+            )
+        }
+        None => {
+            info!(
+                "ポーリング監視を使用します（間隔: {}ms）",
+                polling_interval_ms
+            );
+            let mut watcher = PollingWatcher::new(polling_interval_ms);
+            let shutdown = watcher.add_handler(manager).get_shutdown_channel();
+            (
+                WatcherStop::Polling(shutdown),
+                Box::new(move || watcher.start_watch()),
+            )
+        }
+    })
+}
 
-    // clipboard-rsのstart_watch()は**ブロッキング**実行なので別スレッドで実行
-    std::thread::spawn(move || {
-        println!("🎯 clipboard-rs 監視開始中...");
+/// ウォッチャーを実行し、停止要求以外で終了・パニックした場合は待ち時間を延ばしながら再起動する
+fn supervise(app: AppHandle, capture: CaptureSender, generation: u64, mut start_watch: StartWatch) {
+    let mut backoff = RESTART_BACKOFF_INITIAL;
+
+    loop {
+        info!("clipboard-rs 監視開始中...");
+        let started = Instant::now();
 
         // start_watch()は内部でイベントループを実行し、shutdown_channel.stop()が呼ばれるまで継続
-        watcher.start_watch();
+        let result = std::panic::catch_unwind(AssertUnwindSafe(start_watch));
 
-        println!("🏁 clipboard-rs 監視終了");
-        MONITORING.store(false, Ordering::Relaxed);
-    });
+        if WATCHER_GENERATION.load(Ordering::Relaxed) != generation {
+            info!("clipboard-rs 監視終了");
+            return;
+        }
 
-    println!("✅ clipboard-rs 監視がバックグラウンドで開始されました");
-    Ok(())
+        let reason = match result {
+            Ok(()) => "ウォッチャーが予期せず終了しました".to_string(),
+            Err(panic) => format!("ウォッチャーがパニックしました: {}", panic_message(&panic)),
+        };
+        error!("{}", reason);
+        health::record_error(reason);
+        health::mark_restarting();
+        emit_monitoring_state(&app);
+
+        // 十分に動作していた場合は一時的な障害とみなして待ち時間を戻す
+        if started.elapsed() >= HEALTHY_RUN {
+            backoff = RESTART_BACKOFF_INITIAL;
+        }
+
+        // 再起動に成功するまで待ち時間を倍にしながら繰り返す
+        start_watch = loop {
+            info!("{}秒後にウォッチャーを再起動します", backoff.as_secs());
+            std::thread::sleep(backoff);
+            backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+
+            if WATCHER_GENERATION.load(Ordering::Relaxed) != generation {
+                return;
+            }
+
+            let settings =
+                tauri::async_runtime::block_on(AppSettings::cached()).unwrap_or_default();
+            // クリップボードに接続できない間（ディスプレイの再起動中など）も再試行を続ける
+            let attached = open_event_watcher(settings.watcher_backend).and_then(|event_watcher| {
+                attach_handler(event_watcher, capture.clone(), settings.polling_interval_ms)
+            });
+            let (stop, start_watch) = match attached {
+                Ok(attached) => attached,
+                Err(e) => {
+                    health::record_error(e);
+                    emit_monitoring_state(&app);
+                    continue;
+                }
+            };
+
+            // 待機中に停止・再開された場合は作成したウォッチャーを使わない
+            let mut global_shutdown = lock_shutdown_channel();
+            if WATCHER_GENERATION.load(Ordering::Relaxed) != generation {
+                return;
+            }
+            *global_shutdown = Some(stop);
+            break start_watch;
+        };
+
+        health::record_restart();
+        warn!(
+            restart_count = health::snapshot().restart_count,
+            "ウォッチャーを再起動しました"
+        );
+        emit_monitoring_state(&app);
+    }
 }
 
-/// Rustバックエンドでクリップボード監視を停止
+/// パニックのペイロードからメッセージを取り出す
+fn panic_message(panic: &Box<dyn std::any::Any + Send>) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "不明なエラー".to_string())
+}
+
+/// Rustバックエンドでクリップボード監視を停止（次回起動時も停止したままにする）
 #[tauri::command]
-pub async fn stop_clipboard_monitoring() -> Result<(), String> {
-    println!("🛑 clipboard-rs監視停止要求");
+pub async fn stop_clipboard_monitoring(app: AppHandle) -> Result<(), String> {
+    stop_monitoring(&app).await;
+    lifecycle::set_stopped(true);
+    Ok(())
+}
 
-    // 適切にshutdown_channelを使って停止
+/// クリップボード監視を停止
+pub async fn stop_monitoring(app: &AppHandle) {
+    info!("clipboard-rs監視停止要求");
+
+    // 適切にshutdown_channelを使って停止（世代を進めて再起動の監視も終了させる）
     {
-        let mut global_shutdown = SHUTDOWN_CHANNEL.lock().unwrap();
+        let mut global_shutdown = lock_shutdown_channel();
+        WATCHER_GENERATION.fetch_add(1, Ordering::Relaxed);
         if let Some(shutdown_channel) = global_shutdown.take() {
             shutdown_channel.stop();
-            println!("✅ clipboard-rs shutdown_channel.stop()実行");
+            debug!("shutdown_channel.stop()実行");
         } else {
-            println!("⚠️ shutdown_channelが見つかりません");
+            warn!("shutdown_channelが見つかりません");
         }
     }
 
+    primary::stop();
+
+    // キューに残ったイベントを処理してからワーカーを停止
+    capture::stop_worker().await;
+
     MONITORING.store(false, Ordering::Relaxed);
-    Ok(())
+    health::clear_restarting();
+    emit_monitoring_state(app);
+}
+
+/// 監視状態
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitoringStatus {
+    pub active: bool,                    // 監視中か（ウォッチャーの再起動待ちを含む）
+    pub backend: Option<WatcherBackend>, // 動作中の監視方式
+    #[serde(flatten)]
+    pub pause: PauseStatus,
+    pub dropped_events: u64,
+    pub health: WatcherHealth,
+}
+
+impl MonitoringStatus {
+    /// トレイなどに表示する状態の説明
+    pub fn summary(&self) -> String {
+        if !self.active {
+            return "停止中".to_string();
+        }
+        if self.health.restarting {
+            return "ウォッチャーの再起動待ち".to_string();
+        }
+        if self.pause.paused {
+            return match self.pause.paused_until {
+                Some(until) => match chrono::DateTime::from_timestamp_millis(until) {
+                    Some(until) => format!(
+                        "一時停止中（{}まで）",
+                        until.with_timezone(&chrono::Local).format("%H:%M")
+                    ),
+                    None => "一時停止中".to_string(),
+                },
+                None => "一時停止中（再起動まで）".to_string(),
+            };
+        }
+        "監視中".to_string()
+    }
+}
+
+/// 現在の監視状態
+pub fn monitoring_status() -> MonitoringStatus {
+    MonitoringStatus {
+        active: MONITORING.load(Ordering::Relaxed),
+        backend: lock_shutdown_channel().as_ref().map(WatcherStop::backend),
+        pause: pause::status(),
+        dropped_events: capture::dropped_event_count(),
+        health: health::snapshot(),
+    }
+}
+
+/// 監視状態の変更を通知
+pub fn emit_monitoring_state(app: &AppHandle) {
+    events::emit(app, AppEvent::MonitoringStateChanged(monitoring_status()));
 }
 
 /// 監視状態を取得
 #[tauri::command]
-pub async fn get_monitoring_status() -> Result<bool, String> {
-    let status = MONITORING.load(Ordering::Relaxed);
-    println!("📊 現在の監視状態: {}", status);
+pub async fn get_monitoring_status() -> Result<MonitoringStatus, String> {
+    let status = monitoring_status();
+    debug!("現在の監視状態: {:?}", status);
     Ok(status)
 }
 
+/// キャプチャを一時停止（期間付きの場合は自動で再開）
+#[tauri::command]
+pub async fn pause_clipboard_monitoring(
+    app: AppHandle,
+    duration: PauseDuration,
+) -> Result<PauseStatus, String> {
+    Ok(pause::pause(&app, duration))
+}
+
+/// 一時停止を解除してキャプチャを再開
+#[tauri::command]
+pub async fn resume_clipboard_monitoring(app: AppHandle) -> Result<PauseStatus, String> {
+    pause::resume(&app);
+    Ok(pause::status())
+}
+
 /// clipboard-rsの動作テスト
 #[tauri::command]
 pub async fn test_clipboard_rs() -> Result<String, String> {
-    println!("🧪 clipboard-rs動作テスト開始");
+    info!("clipboard-rs動作テスト開始");
 
     // 1. ClipboardContext作成テスト
     let ctx = ClipboardContext::new().map_err(|e| format!("ClipboardContext作成失敗: {}", e))?;
-    println!("✅ ClipboardContext作成成功");
+    info!("ClipboardContext作成成功");
 
     // 2. 現在のクリップボード内容を取得
     let current_text = ctx
@@ -399,12 +562,12 @@ pub async fn test_clipboard_rs() -> Result<String, String> {
         }
         &current_text[..boundary]
     };
-    println!("✅ クリップボード読み取り成功: {}", preview);
+    debug!("クリップボード読み取り成功: {}", preview);
 
     // 3. ClipboardWatcherContext作成テスト
     let _watcher: ClipboardWatcherContext<ClipboardManager> = ClipboardWatcherContext::new()
         .map_err(|e| format!("ClipboardWatcherContext作成失敗: {}", e))?;
-    println!("✅ ClipboardWatcherContext作成成功");
+    info!("ClipboardWatcherContext作成成功");
 
     Ok(format!(
         "clipboard-rsテスト成功 - 現在のクリップボード: {}",
@@ -421,13 +584,65 @@ pub async fn get_clipboard_text() -> Result<String, String> {
         .map_err(|e| format!("クリップボード読み取りエラー: {}", e))
 }
 
-/// クリップボードにテキストを設定
+/// クリップボードにテキストを設定し、履歴のアイテムなら使用として記録
+///
+/// IDを省略した場合は内容が同じアイテムを探す。
 #[tauri::command]
-pub async fn set_clipboard_text(text: String) -> Result<(), String> {
+pub async fn set_clipboard_text(
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
+    text: String,
+    item_id: Option<String>,
+) -> Result<(), String> {
     let ctx = ClipboardContext::new().map_err(|e| format!("ClipboardContext作成エラー: {}", e))?;
 
-    ctx.set_text(text)
-        .map_err(|e| format!("クリップボード書き込みエラー: {}", e))
+    // 監視側で新しいコピーとして保存しないよう、書き込む前に登録しておく
+    let token = own_writes::register_text(&text);
+    let content_hash = hash::content_hash(&text, false);
+    ctx.set_text(text).map_err(|e| {
+        own_writes::cancel(token);
+        format!("クリップボード書き込みエラー: {}", e)
+    })?;
+
+    let item_id = match item_id {
+        Some(id) => Some(id),
+        None => db
+            .find_item_id_by_hash(&content_hash, false, ClipboardSelection::Clipboard)
+            .await
+            .map_err(|e| format!("重複チェックエラー: {}", e))?,
+    };
+    if let Some(item_id) = item_id {
+        capture::record_item_use(&app, &db, &item_id).await;
+    }
+    Ok(())
+}
+
+/// 保存済みアイテムの全形式をクリップボードに書き戻し（アプリ固有の追加形式も含む）、使用として記録
+#[tauri::command]
+pub async fn restore_clipboard_item(
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
+    id: String,
+) -> Result<(), String> {
+    let item = db
+        .get_item_by_id(&id)
+        .await
+        .map_err(|e| format!("アイテム取得エラー: {}", e))?;
+
+    let contents = formats::to_clipboard_contents(&item);
+    if contents.is_empty() {
+        return Err("クリップボードに書き戻せる形式がありません".to_string());
+    }
+
+    let ctx = ClipboardContext::new().map_err(|e| format!("ClipboardContext作成エラー: {}", e))?;
+    let token = own_writes::register_item(&item);
+    ctx.set(contents).map_err(|e| {
+        own_writes::cancel(token);
+        format!("クリップボード書き込みエラー: {}", e)
+    })?;
+
+    capture::record_item_use(&app, &db, &item.id).await;
+    Ok(())
 }
 
 /// クリップボードにテキストがあるかチェック
@@ -450,266 +665,141 @@ pub async fn clear_clipboard_text() -> Result<(), String> {
         .map_err(|e| format!("クリップボードクリアエラー: {}", e))
 }
 
-/// クリップボードで利用可能な形式を検出
-fn detect_clipboard_formats(ctx: &ClipboardContext) -> Vec<String> {
-    let mut formats = Vec::new();
-
-    if ctx.has(ContentFormat::Text) {
-        formats.push("text/plain".to_string());
-    }
-    if ctx.has(ContentFormat::Html) {
-        formats.push("text/html".to_string());
-    }
-    if ctx.has(ContentFormat::Rtf) {
-        formats.push("text/rtf".to_string());
-    }
-    if ctx.has(ContentFormat::Image) {
-        formats.push("image/png".to_string());
-    }
-    if ctx.has(ContentFormat::Files) {
-        formats.push("application/x-file-list".to_string());
-    }
-
-    formats
+/// 現在のクリップボードの内容を記録した場合に保存される形式を取得
+#[tauri::command]
+pub async fn get_clipboard_formats() -> Result<Vec<String>, String> {
+    let ctx = ClipboardContext::new().map_err(|e| format!("ClipboardContext作成エラー: {}", e))?;
+    let settings = AppSettings::cached().await.unwrap_or_default();
+    let targets = ctx.available_formats().unwrap_or_default();
+
+    Ok(formats::available(
+        &ctx,
+        &targets,
+        &settings.extra_capture_formats,
+        settings.derive_markdown_from_html,
+    ))
 }
 
 /// 全ての利用可能な形式のコンテンツを収集
+///
+/// プライマリ形式は優先順位 Text > Files > Image > RTF > HTML > 追加形式 で決定する。
+/// Textを最優先にすることで、URLなどの内容の種類が適切に判定される。
 fn collect_all_format_contents(
     ctx: &ClipboardContext,
-) -> std::collections::HashMap<String, String> {
-    let mut contents = std::collections::HashMap::new();
+    targets: &[String],
+    extra_formats: &[String],
+) -> Option<CaptureEvent> {
+    let mut format_contents = HashMap::new();
+    let mut binary_contents = HashMap::new();
+    let mut images = HashMap::new();
+    let mut primary_format: Option<String> = None;
 
     // テキスト形式
     if ctx.has(ContentFormat::Text) {
-        if let Ok(text) = ctx.get_text() {
-            let format = analyze_text_format(&text);
-            contents.insert(format, text);
-        }
-    }
-
-    // ファイルリスト形式
-    if ctx.has(ContentFormat::Files) {
-        if let Ok(files) = ctx.get_files() {
-            let files_text = files.join("\n");
-            contents.insert("application/x-file-list".to_string(), files_text);
-        }
-    }
-
-    // 画像形式
-    if ctx.has(ContentFormat::Image) {
-        if let Ok(image_data) = ctx.get_image() {
-            // RustImageDataの利用可能なメソッドを試してみる
-            println!("📸 画像データ取得成功");
-
-            // 画像をファイルに一時保存して読み込む方法を試す
-            let temp_path = std::env::temp_dir().join("clipboard_debug.png");
-            match image_data.save_to_path(&temp_path.to_string_lossy()) {
-                Ok(_) => {
-                    // ファイルからBase64を作成
-                    match std::fs::read(&temp_path) {
-                        Ok(bytes) => {
-                            // 画像サイズ制限（5MB）
-                            const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024;
-                            if bytes.len() > MAX_IMAGE_SIZE {
-                                println!(
-                                    "📸 画像サイズが大きすぎます: {}バイト (上限: {}MB)",
-                                    bytes.len(),
-                                    MAX_IMAGE_SIZE / 1024 / 1024
-                                );
-                                let size_info = format!(
-                                    "[画像データ: {}KB - サイズ制限により表示不可]",
-                                    bytes.len() / 1024
-                                );
-                                contents.insert("image/png".to_string(), size_info);
-                            } else {
-                                let base64_data = BASE64.encode(&bytes);
-                                let data_url = format!("data:image/png;base64,{}", base64_data);
-                                contents.insert("image/png".to_string(), data_url);
-                                println!("📸 画像データ保存成功: {}KB", bytes.len() / 1024);
-                            }
-
-                            // 一時ファイル削除
-                            let _ = std::fs::remove_file(&temp_path);
-                        }
-                        Err(e) => {
-                            println!("ファイル読み込みエラー: {}", e);
-                            // Windows特有のエラーでもプレースホルダー保存
-                            let error_info = format!("[画像データ: 読み込みエラー - {}]", e);
-                            contents.insert("image/png".to_string(), error_info);
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!(
-                        "画像保存エラー: {} (Windows OSError(0)は正常終了の場合があります)",
-                        e
-                    );
-                    // Windows特有のOSError(0)の場合でもプレースホルダー保存
-                    if e.to_string().contains("OSError(0)") {
-                        let placeholder = "[画像データ: Windows取得エラー]".to_string();
-                        contents.insert("image/png".to_string(), placeholder);
-                    } else {
-                        let error_info = format!("[画像データ: 保存エラー - {}]", e);
-                        contents.insert("image/png".to_string(), error_info);
-                    }
-                }
+        match ctx.get_text() {
+            Ok(text) if !text.is_empty() => {
+                // 内容の種類（URL・JSONなど）は保存時に別途判定する
+                let format = "text/plain".to_string();
+                primary_format.get_or_insert_with(|| format.clone());
+                format_contents.insert(format, text);
             }
+            Ok(_) => {}
+            Err(e) => error!("テキスト取得エラー: {}", e),
         }
     }
 
-    // RTF形式
-    if ctx.has(ContentFormat::Rtf) {
-        if let Ok(rtf) = ctx.get_rich_text() {
-            contents.insert("text/rtf".to_string(), rtf);
-        }
-    }
-
-    // HTML形式
-    if ctx.has(ContentFormat::Html) {
-        if let Ok(html) = ctx.get_html() {
-            contents.insert("text/html".to_string(), html);
-        }
-    }
-
-    contents
-}
-
-/// 優先順位に従ってクリップボードコンテンツを取得
-fn get_clipboard_content_by_priority(ctx: &ClipboardContext) -> Result<(String, String), String> {
-    // 優先順位: Text > Files > Image > RTF > HTML
-    // Textを最優先にすることで、URLなどが適切に判定される
-
-    // 最優先: テキスト（URLやプレーンテキストを適切に処理）
-    if ctx.has(ContentFormat::Text) {
-        let text = ctx
-            .get_text()
-            .map_err(|e| format!("テキスト取得エラー: {}", e))?;
-        // テキストの内容を詳細分析してより正確な形式判定
-        let format = analyze_text_format(&text);
-        return Ok((text, format));
-    }
-
+    // ファイルリスト形式
     if ctx.has(ContentFormat::Files) {
         match ctx.get_files() {
             Ok(files) => {
                 let files_text = files.join("\n");
-                return Ok((files_text, "application/x-file-list".to_string()));
+                primary_format.get_or_insert_with(|| "application/x-file-list".to_string());
+                format_contents.insert("application/x-file-list".to_string(), files_text);
             }
-            Err(e) => println!("ファイルリスト取得エラー: {}", e),
+            Err(e) => error!("ファイルリスト取得エラー: {}", e),
         }
     }
 
+    // 画像形式（一時ファイルを使わずメモリ上でPNGにエンコード）
     if ctx.has(ContentFormat::Image) {
         match ctx.get_image() {
             Ok(image_data) => {
-                // RustImageDataの利用可能なメソッドを試してみる
-                println!("📸 画像データ取得成功");
-
-                // 画像をファイルに一時保存して読み込む方法を試す
-                let temp_path = std::env::temp_dir().join("clipboard_priority_debug.png");
-                match image_data.save_to_path(&temp_path.to_string_lossy()) {
-                    Ok(_) => {
-                        // ファイルからBase64を作成
-                        match std::fs::read(&temp_path) {
-                            Ok(bytes) => {
-                                // 画像サイズ制限（5MB）
-                                const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024;
-                                if bytes.len() > MAX_IMAGE_SIZE {
-                                    println!(
-                                        "📸 画像サイズが大きすぎます: {}バイト (上限: {}MB)",
-                                        bytes.len(),
-                                        MAX_IMAGE_SIZE / 1024 / 1024
-                                    );
-                                    let size_info = format!(
-                                        "[画像データ: {}KB - サイズ制限により表示不可]",
-                                        bytes.len() / 1024
-                                    );
-
-                                    // 一時ファイル削除
-                                    let _ = std::fs::remove_file(&temp_path);
-
-                                    return Ok((size_info, "image/png".to_string()));
-                                } else {
-                                    let base64_data = BASE64.encode(&bytes);
-                                    let data_url = format!("data:image/png;base64,{}", base64_data);
-                                    println!("📸 画像データ変換成功: {}KB", bytes.len() / 1024);
-
-                                    // 一時ファイル削除
-                                    let _ = std::fs::remove_file(&temp_path);
-
-                                    return Ok((data_url, "image/png".to_string()));
-                                }
-                            }
-                            Err(e) => {
-                                println!("ファイル読み込みエラー: {}", e);
-                                return Ok((
-                                    "[画像データ: 読み込みエラー]".to_string(),
-                                    "image/png".to_string(),
-                                ));
+                debug!("画像データ取得成功");
+                match imaging::encode_png(&image_data) {
+                    Ok(bytes) => {
+                        // 大きな画像もそのまま保存する（閾値を超える場合はBLOBストアに退避される）
+                        debug!("画像データ変換成功: {}KB", bytes.len() / 1024);
+                        // メタデータとサムネイルを生成（失敗しても元画像は保存する）
+                        match imaging::capture_image(&image_data, &bytes) {
+                            Ok(image) => {
+                                images.insert("image/png".to_string(), image);
                             }
+                            Err(e) => error!("サムネイル生成エラー: {}", e),
                         }
+                        binary_contents.insert("image/png".to_string(), bytes);
+                        primary_format.get_or_insert_with(|| "image/png".to_string());
                     }
-                    Err(e) => {
-                        println!("画像保存エラー: {}", e);
-                        return Ok((
-                            "[画像データ: 保存エラー]".to_string(),
-                            "image/png".to_string(),
-                        ));
-                    }
+                    Err(e) => error!("画像エンコードエラー: {}", e),
                 }
             }
             Err(e) => {
-                println!(
+                warn!(
                     "画像取得エラー: {} (Windows OSError(0)は正常終了の場合があります)",
                     e
                 );
                 // Windows特有のOSError(0)の場合、画像が実際に存在する可能性があるため
                 // プレースホルダーとして画像形式で保存
                 if e.to_string().contains("OSError(0)") {
-                    println!("📸 Windows OSError(0) - 画像データは存在する可能性があります");
-                    println!("💡 clipboard-rsの制限: 将来的にarboardライブラリへの移行を検討");
-                    return Ok((
-                        "[画像データ: Windows取得エラー - arboard移行検討中]".to_string(),
-                        "image/png".to_string(),
-                    ));
+                    debug!("clipboard-rsの制限: 将来的にarboardライブラリへの移行を検討");
+                    let placeholder = "[画像データ: Windows取得エラー]".to_string();
+                    format_contents.insert("image/png".to_string(), placeholder);
+                    primary_format.get_or_insert_with(|| "image/png".to_string());
                 }
             }
         }
     }
 
+    // RTF形式
     if ctx.has(ContentFormat::Rtf) {
         match ctx.get_rich_text() {
-            Ok(rtf) => return Ok((rtf, "text/rtf".to_string())),
-            Err(e) => println!("RTF取得エラー: {}", e),
+            Ok(rtf) => {
+                primary_format.get_or_insert_with(|| "text/rtf".to_string());
+                format_contents.insert("text/rtf".to_string(), rtf);
+            }
+            Err(e) => error!("RTF取得エラー: {}", e),
         }
     }
 
     // 最低優先度: HTML（現在パース機能なし）
     if ctx.has(ContentFormat::Html) {
         match ctx.get_html() {
-            Ok(html) => return Ok((html, "text/html".to_string())),
-            Err(e) => println!("HTML取得エラー: {}", e),
+            Ok(html) => {
+                primary_format.get_or_insert_with(|| "text/html".to_string());
+                format_contents.insert("text/html".to_string(), html);
+            }
+            Err(e) => error!("HTML取得エラー: {}", e),
         }
     }
 
-    Err("利用可能なクリップボード形式がありません".to_string())
-}
-
-/// テキストの内容を分析してより正確な形式を判定
-fn analyze_text_format(text: &str) -> String {
-    if text.starts_with("http://") || text.starts_with("https://") {
-        "text/uri-list".to_string()
-    } else if text.starts_with("data:image/") {
-        "image/png".to_string()
-    } else if text.starts_with("data:") {
-        "application/octet-stream".to_string()
-    } else if text.contains("<html") || text.contains("</html>") {
-        "text/html".to_string()
-    } else if text.starts_with("{\\rtf") {
-        "text/rtf".to_string()
-    } else if text.starts_with("/") || text.starts_with("C:\\") || text.contains("\\") {
-        "application/x-file-path".to_string()
-    } else {
-        "text/plain".to_string()
+    // 設定された追加形式（SVG・アプリ固有形式など）を生のバイト列で保存
+    let extra_contents = formats::collect_extra_formats(ctx, targets, extra_formats, |target| {
+        formats::is_builtin(target)
+            || format_contents.contains_key(target)
+            || binary_contents.contains_key(target)
+    });
+    for (format, bytes) in extra_contents {
+        primary_format.get_or_insert_with(|| format.clone());
+        binary_contents.insert(format, bytes);
     }
+
+    primary_format.map(|primary_format| CaptureEvent {
+        item: NewClipboardItem {
+            primary_format,
+            format_contents,
+            binary_contents,
+            images,
+            ..Default::default()
+        },
+        concealed: false,
+    })
 }
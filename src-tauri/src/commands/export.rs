@@ -1,9 +1,10 @@
-use crate::database::{Database, DisplayClipboardItem};
+use crate::database::{Database, DisplayClipboardItem, NewClipboardItem};
+use crate::events::{self, AppEvent};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
-use tauri::State;
-use tokio::sync::Mutex;
+use tauri::{AppHandle, State};
 
 /// エクスポート用のデータ構造
 #[derive(Serialize, Deserialize)]
@@ -16,12 +17,9 @@ pub struct ExportData {
 
 /// クリップボード履歴をJSONフォーマットでエクスポート
 #[tauri::command]
-pub async fn export_clipboard_history_json(
-    db_state: State<'_, Arc<Mutex<Database>>>,
-) -> Result<String, String> {
-    let db = db_state.lock().await;
+pub async fn export_clipboard_history_json(db: State<'_, Arc<Database>>) -> Result<String, String> {
     let items = db
-        .get_display_history(None, None)
+        .get_export_history(None, None)
         .await
         .map_err(|e| format!("履歴取得エラー: {}", e))?;
 
@@ -38,12 +36,9 @@ pub async fn export_clipboard_history_json(
 
 /// クリップボード履歴をCSVフォーマットでエクスポート
 #[tauri::command]
-pub async fn export_clipboard_history_csv(
-    db_state: State<'_, Arc<Mutex<Database>>>,
-) -> Result<String, String> {
-    let db = db_state.lock().await;
+pub async fn export_clipboard_history_csv(db: State<'_, Arc<Database>>) -> Result<String, String> {
     let items = db
-        .get_display_history(None, None)
+        .get_export_history(None, None)
         .await
         .map_err(|e| format!("履歴取得エラー: {}", e))?;
 
@@ -73,40 +68,48 @@ pub async fn export_clipboard_history_csv(
 /// JSONからクリップボード履歴をインポート
 #[tauri::command]
 pub async fn import_clipboard_history_json(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
     json_data: String,
 ) -> Result<usize, String> {
     let export_data: ExportData = serde_json::from_str(&json_data)
         .map_err(|e| format!("JSONデシリアライゼーションエラー: {}", e))?;
 
-    let db = db_state.lock().await;
-    let mut imported_count = 0;
+    let mut imported_ids = Vec::new();
+    let mut imported_hashes = HashSet::new();
 
     for item in export_data.items {
-        // 重複チェック
-        let existing_items = db
-            .get_display_history(Some(1000), None)
+        let new_item = NewClipboardItem::from_single_content(
+            &item.content,
+            &item.content_type,
+            item.source_app.as_deref(),
+        );
+
+        // 重複チェック（ファイル内の重複と既存アイテムをコンテンツハッシュで判定）
+        let content_hash = new_item.content_hash(false);
+        if !imported_hashes.insert(content_hash.clone()) {
+            continue;
+        }
+        let existing_id = db
+            .find_item_id_by_hash(&content_hash, false, new_item.selection)
             .await
-            .map_err(|e| format!("既存履歴取得エラー: {}", e))?;
-
-        let is_duplicate = existing_items.iter().any(|existing| {
-            existing.content == item.content && existing.timestamp == item.timestamp
-        });
-
-        if !is_duplicate {
-            let _saved_item = db
-                .save_clipboard_item(
-                    &item.content,
-                    &item.content_type,
-                    item.source_app.as_deref(),
-                )
-                .await
-                .map_err(|e| format!("アイテム保存エラー: {}", e))?;
-
-            imported_count += 1;
+            .map_err(|e| format!("重複チェックエラー: {}", e))?;
+        if existing_id.is_some() {
+            continue;
         }
+
+        let saved_item = db
+            .save_clipboard_item_normalized(&new_item)
+            .await
+            .map_err(|e| format!("アイテム保存エラー: {}", e))?;
+
+        imported_ids.push(saved_item.id);
     }
 
+    let imported_count = imported_ids.len();
+    if imported_count > 0 {
+        events::emit(&app, AppEvent::ItemAdded { ids: imported_ids });
+    }
     Ok(imported_count)
 }
 
@@ -1,30 +1,31 @@
-use crate::database::{Database, DisplayClipboardItem};
+use crate::capture::{classifier::ContentKind, files};
+use crate::database::{ClipboardSelection, Database, DisplayClipboardItem, FileEntry};
+use crate::events::{self, AppEvent, DeleteReason};
 use std::sync::Arc;
-use tauri::State;
-use tokio::sync::Mutex;
+use tauri::{AppHandle, State};
+use tracing::{debug, error, info};
 
 /// クリップボード履歴を取得
 #[tauri::command]
 pub async fn get_clipboard_history(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    db: State<'_, Arc<Database>>,
     limit: Option<u32>,
     offset: Option<u32>,
+    selection: Option<ClipboardSelection>,
 ) -> Result<Vec<DisplayClipboardItem>, String> {
-    println!(
-        "get_clipboard_history コマンド呼び出し: limit={:?}, offset={:?}",
-        limit, offset
+    debug!(
+        "get_clipboard_history コマンド呼び出し: limit={:?}, offset={:?}, selection={:?}",
+        limit, offset, selection
     );
 
-    let db = db_state.lock().await;
-
-    match db.get_display_history(limit, offset).await {
+    match db.get_display_history(limit, offset, selection).await {
         Ok(items) => {
-            println!("履歴取得成功: {} 件", items.len());
+            debug!("履歴取得成功: {} 件", items.len());
             Ok(items)
         }
         Err(e) => {
             let error_msg = format!("履歴取得エラー: {}", e);
-            println!("エラー: {}", error_msg);
+            error!("エラー: {}", error_msg);
             Err(error_msg)
         }
     }
@@ -33,113 +34,141 @@ pub async fn get_clipboard_history(
 /// クリップボード履歴を検索
 #[tauri::command]
 pub async fn search_clipboard_history(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    db: State<'_, Arc<Database>>,
     query: String,
     limit: Option<u32>,
+    selection: Option<ClipboardSelection>,
 ) -> Result<Vec<DisplayClipboardItem>, String> {
-    let db = db_state.lock().await;
     // 正規化された検索結果をDisplayClipboardItemに変換
     let search_results = db
-        .search_history(&query, limit)
+        .search_history(&query, limit, selection)
         .await
         .map_err(|e| format!("履歴検索エラー: {}", e))?;
 
-    let mut display_results = Vec::new();
-    for item in search_results {
-        let available_formats: Vec<String> =
-            item.contents.iter().map(|c| c.format.clone()).collect();
-        let format_contents: std::collections::HashMap<String, String> = item
-            .contents
-            .iter()
-            .map(|c| (c.format.clone(), c.content.clone()))
-            .collect();
-
-        let primary_content = format_contents
-            .get(&item.primary_format)
-            .cloned()
-            .unwrap_or_else(|| "[No content]".to_string());
-
-        display_results.push(DisplayClipboardItem {
-            id: item.id,
-            content: primary_content,
-            content_type: item.primary_format.clone(),
-            timestamp: item.timestamp,
-            is_favorite: item.is_favorite,
-            source_app: item.source_app,
-            created_at: item.created_at,
-            available_formats: Some(available_formats),
-            format_contents: Some(format_contents),
-        });
-    }
-
-    Ok(display_results)
+    Ok(search_results
+        .into_iter()
+        .map(DisplayClipboardItem::from)
+        .collect())
 }
 
 /// 特定のアイテムを取得
 #[tauri::command]
 pub async fn get_clipboard_item(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    db: State<'_, Arc<Database>>,
     id: String,
 ) -> Result<Option<DisplayClipboardItem>, String> {
-    let db = db_state.lock().await;
-    let items = db
-        .get_display_history(None, None)
-        .await
-        .map_err(|e| format!("履歴取得エラー: {}", e))?;
-
-    let item = items.into_iter().find(|item| item.id == id);
-    Ok(item)
+    // 一覧はサムネイル・伏せた内容を返すため、元データを含むアイテムをIDで直接取得
+    match db.get_item_by_id(&id).await {
+        Ok(item) => Ok(Some(DisplayClipboardItem::revealed(item))),
+        Err(e) => match e.downcast_ref::<sqlx::Error>() {
+            Some(sqlx::Error::RowNotFound) => Ok(None),
+            _ => Err(format!("アイテム取得エラー: {}", e)),
+        },
+    }
 }
 
 /// お気に入りの切り替え
 #[tauri::command]
 pub async fn toggle_favorite(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
     id: String,
 ) -> Result<bool, String> {
-    let db = db_state.lock().await;
-    db.toggle_favorite(&id)
+    let is_favorite = db
+        .toggle_favorite(&id)
         .await
-        .map_err(|e| format!("お気に入り更新エラー: {}", e))
+        .map_err(|e| format!("お気に入り更新エラー: {}", e))?;
+
+    events::item_updated(&app, &id);
+    Ok(is_favorite)
+}
+
+/// アイテムに含まれるファイルの存在・サイズなどを再確認（削除・移動されたファイルの検出用）
+#[tauri::command]
+pub async fn revalidate_item_files(
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
+    id: String,
+) -> Result<Vec<FileEntry>, String> {
+    let item = db
+        .get_item_by_id(&id)
+        .await
+        .map_err(|e| format!("アイテム取得エラー: {}", e))?;
+
+    // 解決済みの情報がなければ（以前のバージョンで保存したアイテム）保存内容から解決する
+    let entries = if !item.files.is_empty() {
+        files::resolve_all(item.files.iter().map(|entry| entry.path.as_str()))
+    } else if let Some(list) = item
+        .contents
+        .iter()
+        .find(|content| content.format == "application/x-file-list")
+    {
+        files::resolve_all(list.content.lines())
+    } else if item.kind.as_deref() == Some(ContentKind::FilePath.as_str()) {
+        let primary = item
+            .contents
+            .iter()
+            .find(|content| content.format == item.primary_format);
+        files::resolve_all(primary.map(|content| content.content.as_str()))
+    } else {
+        Vec::new()
+    };
+
+    db.save_file_entries(&id, &entries)
+        .await
+        .map_err(|e| format!("ファイル情報保存エラー: {}", e))?;
+
+    let missing = entries.iter().filter(|entry| !entry.exists).count();
+    info!(
+        "ファイルを再確認: {} ({}件中{}件が見つかりません)",
+        id,
+        entries.len(),
+        missing
+    );
+
+    events::item_updated(&app, &id);
+    Ok(entries)
 }
 
 /// アイテムを削除
 #[tauri::command]
 pub async fn delete_clipboard_item(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
     id: String,
 ) -> Result<(), String> {
-    let db = db_state.lock().await;
     db.delete_item(&id)
         .await
-        .map_err(|e| format!("アイテム削除エラー: {}", e))
+        .map_err(|e| format!("アイテム削除エラー: {}", e))?;
+
+    events::items_deleted(&app, vec![id], DeleteReason::User);
+    Ok(())
 }
 
 /// 履歴をクリア
 #[tauri::command]
 pub async fn clear_clipboard_history(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
 ) -> Result<(), String> {
-    let db = db_state.lock().await;
     db.clear_history()
         .await
-        .map_err(|e| format!("履歴クリアエラー: {}", e))
+        .map_err(|e| format!("履歴クリアエラー: {}", e))?;
+
+    events::emit(&app, AppEvent::HistoryCleared);
+    Ok(())
 }
 
 /// 履歴の統計を取得
 #[tauri::command]
-pub async fn get_clipboard_stats(
-    db_state: State<'_, Arc<Mutex<Database>>>,
-) -> Result<ClipboardStats, String> {
-    let db = db_state.lock().await;
-
+pub async fn get_clipboard_stats(db: State<'_, Arc<Database>>) -> Result<ClipboardStats, String> {
     let total_count = db
         .get_item_count()
         .await
         .map_err(|e| format!("統計取得エラー: {}", e))?;
 
     let items = db
-        .get_display_history(None, None)
+        .get_display_history(None, None, None)
         .await
         .map_err(|e| format!("履歴取得エラー: {}", e))?;
 
@@ -163,13 +192,17 @@ pub async fn get_clipboard_stats(
 /// 古いアイテムをクリーンアップ
 #[tauri::command]
 pub async fn cleanup_old_items(
-    db_state: State<'_, Arc<Mutex<Database>>>,
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
     max_items: usize,
 ) -> Result<(), String> {
-    let db = db_state.lock().await;
-    db.cleanup_old_items(max_items)
+    let ids = db
+        .cleanup_old_items(max_items)
         .await
-        .map_err(|e| format!("クリーンアップエラー: {}", e))
+        .map_err(|e| format!("クリーンアップエラー: {}", e))?;
+
+    events::items_deleted(&app, ids, DeleteReason::Cleanup);
+    Ok(())
 }
 
 /// 履歴統計の構造体
@@ -0,0 +1,16 @@
+use crate::logging;
+
+/// 既定で返すログの行数
+const DEFAULT_LOG_LINES: usize = 500;
+
+/// 直近のログを取得（古い順）
+#[tauri::command]
+pub async fn get_recent_logs(limit: Option<usize>) -> Result<Vec<String>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LOG_LINES);
+
+    // ファイルの読み込みは非同期ランタイムのスレッドを塞がないように別スレッドで行う
+    tauri::async_runtime::spawn_blocking(move || logging::recent_lines(limit))
+        .await
+        .map_err(|e| format!("ログ読み込みエラー: {}", e))?
+        .map_err(|e| format!("ログ読み込みエラー: {}", e))
+}
@@ -1,3 +1,5 @@
+use tracing::{debug, error, info};
+
 // クリップボード操作コマンド
 pub mod clipboard;
 pub use clipboard::*;
@@ -14,21 +16,31 @@ pub use settings::*;
 pub mod export;
 pub use export::*;
 
+// アプリ別キャプチャルールコマンド
+pub mod app_rules;
+pub use app_rules::*;
+
+// キャプチャルールコマンド
+pub mod capture_rules;
+pub use capture_rules::*;
+
+// ログ取得コマンド
+pub mod logs;
+pub use logs::*;
+
 // テスト用コマンド
 #[tauri::command]
 pub fn test_command() -> String {
-    println!("test_command が呼ばれました");
+    info!("test_command が呼ばれました");
     "テストコマンド成功".to_string()
 }
 
 // テスト用データ追加
 #[tauri::command]
 pub async fn add_test_data(
-    db_state: tauri::State<'_, std::sync::Arc<tokio::sync::Mutex<crate::database::Database>>>,
+    db: tauri::State<'_, std::sync::Arc<crate::database::Database>>,
 ) -> Result<String, String> {
-    println!("add_test_data コマンド呼び出し");
-
-    let db = db_state.lock().await;
+    info!("add_test_data コマンド呼び出し");
 
     // テストデータを追加
     let test_items = vec![
@@ -48,10 +60,10 @@ pub async fn add_test_data(
         {
             Ok(_) => {
                 added_count += 1;
-                println!("テストデータ追加成功: {}", content);
+                debug!("テストデータ追加成功: {}", content);
             }
             Err(e) => {
-                println!("テストデータ追加失敗: {} - エラー: {}", content, e);
+                error!("テストデータ追加失敗: {} - エラー: {}", content, e);
             }
         }
     }
@@ -1,7 +1,45 @@
+use crate::capture::app_filter::{AppCaptureRule, AppFilter, AppRuleAction};
+use crate::capture::formats;
+use crate::capture::poller::MIN_POLLING_INTERVAL_MS;
+use crate::capture::rules::{CaptureRule, RuleSet};
+use crate::capture::sensitive::{self, SensitiveDetector, SensitiveRule};
+use crate::capture::size_limit::{self, SizeLimit};
+use crate::events;
+use crate::logging::{self, LogLevel};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+// 読み込み済みの設定（保存のたびに更新し、クリップボードの変更ごとにファイルを読まないようにする）
+static CACHE: StdMutex<Option<AppSettings>> = StdMutex::new(None);
+
+// 読み込み済みの設定のルールをコンパイルしたもの（保存のたびに作り直す）
+static MATCHERS: StdMutex<Option<Arc<CaptureMatchers>>> = StdMutex::new(None);
+
+/// キャプチャ時に使う、設定のルールの正規表現をコンパイルしたもの
+pub struct CaptureMatchers {
+    pub app_filter: AppFilter,
+    pub capture_rules: RuleSet,
+    pub sensitive: SensitiveDetector,
+}
+
+impl CaptureMatchers {
+    fn new(settings: &AppSettings) -> Self {
+        Self {
+            app_filter: AppFilter::new(
+                &settings.app_capture_rules,
+                settings.app_capture_default_action,
+            ),
+            capture_rules: RuleSet::new(&settings.capture_rules),
+            sensitive: SensitiveDetector::new(&settings.sensitive_rules),
+        }
+    }
+}
 
 /// アプリケーション情報の構造体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,14 +54,75 @@ pub struct AppInfo {
     pub build_date: String,         // ビルド日時
 }
 
+/// 重複コンテンツをコピーした際の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// 重複していても新しいアイテムとして保存
+    KeepAll,
+    /// 既存アイテムを先頭に移動してコピー回数を加算
+    #[default]
+    MoveToTop,
+    /// 何もしない（既存アイテムをそのまま残す）
+    Ignore,
+}
+
+/// パスワードマネージャーなどが機密データとしてマークしたコピーの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcealedPolicy {
+    /// 保存しない
+    #[default]
+    Skip,
+    /// 内容を伏せたプレースホルダーを一定時間だけ保存
+    Redact,
+}
+
+/// クリップボード監視の方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatcherBackend {
+    /// 変更イベントを使い、ウォッチャーを作成できない場合はポーリングに切り替える
+    #[default]
+    Auto,
+    /// 変更イベントのみ
+    Event,
+    /// 一定間隔でクリップボードを確認
+    Polling,
+}
+
 /// アプリケーション設定の構造体（簡素化）
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
     pub max_history_items: u32,
     pub hotkeys: HashMap<String, String>,
     pub theme: String,
     pub export_format: String,
     pub notifications_enabled: bool,
+    pub duplicate_policy: DuplicatePolicy,
+    pub ignore_whitespace_duplicates: bool, // 空白の差異を無視して重複判定
+    pub blob_threshold_kb: u32,             // これを超えるコンテンツはディスク上のBLOBストアに保存
+    pub concealed_policy: ConcealedPolicy,
+    pub concealed_placeholder_ttl_secs: u64, // プレースホルダーを残す秒数
+    pub sensitive_detection_enabled: bool,
+    pub sensitive_ttl_secs: u64, // 機密データを自動削除するまでの秒数（0は削除しない）
+    pub sensitive_rules: Vec<SensitiveRule>, // 組み込みルールに追加するユーザー定義ルール
+    pub app_capture_default_action: AppRuleAction, // どのアプリ別ルールにも一致しない場合の動作
+    pub app_capture_rules: Vec<AppCaptureRule>, // 上から順に評価するアプリ別ルール
+    pub monitor_on_startup: bool, // 起動時にクリップボード監視を開始（前回停止したまま終了した場合を除く）
+    pub watcher_backend: WatcherBackend,
+    pub polling_interval_ms: u64,            // ポーリング監視の確認間隔
+    pub primary_selection_enabled: bool,     // X11のPRIMARYセレクション（テキスト選択）も記録
+    pub primary_selection_debounce_ms: u64,  // 選択操作が落ち着いてから記録するまでの待ち時間
+    pub extra_capture_formats: Vec<String>,  // 追加で保存する形式（末尾の*で前方一致）
+    pub derive_markdown_from_html: bool,     // HTMLからMarkdownも派生させる（テキストは常に派生）
+    pub capture_coalesce_window_ms: u64, // この時間内の連続した変更を1つのアイテムにまとめる（0は無効）
+    pub capture_rate_limit_per_minute: u32, // コピー元アプリごとの1分あたりの記録数の上限（0は無制限）
+    pub move_reused_item_to_top: bool,      // ClipOneから書き戻したアイテムを履歴の先頭に移動
+    pub capture_rules: Vec<CaptureRule>, // 上から順に評価するキャプチャルール（タグ付け・変換など）
+    pub content_size_limits: Vec<SizeLimit>, // 形式ごとのサイズ上限（上から順に最初に一致したもの）
+    pub log_level: LogLevel, // ログの出力レベル（debug以上で取得した内容のサイズ・ハッシュも出力）
 }
 
 impl Default for AppSettings {
@@ -38,6 +137,29 @@ impl Default for AppSettings {
             theme: "dark".to_string(),
             export_format: "json".to_string(),
             notifications_enabled: true,
+            duplicate_policy: DuplicatePolicy::default(),
+            ignore_whitespace_duplicates: false,
+            blob_threshold_kb: (crate::database::DEFAULT_BLOB_THRESHOLD / 1024) as u32,
+            concealed_policy: ConcealedPolicy::default(),
+            concealed_placeholder_ttl_secs: 60,
+            sensitive_detection_enabled: true,
+            sensitive_ttl_secs: 3600,
+            sensitive_rules: Vec::new(),
+            app_capture_default_action: AppRuleAction::default(),
+            app_capture_rules: Vec::new(),
+            monitor_on_startup: true,
+            watcher_backend: WatcherBackend::default(),
+            polling_interval_ms: 500,
+            primary_selection_enabled: false,
+            primary_selection_debounce_ms: 1000,
+            extra_capture_formats: formats::default_extra_formats(),
+            derive_markdown_from_html: false,
+            capture_coalesce_window_ms: 300,
+            capture_rate_limit_per_minute: 60,
+            move_reused_item_to_top: true,
+            capture_rules: Vec::new(),
+            content_size_limits: size_limit::default_size_limits(),
+            log_level: LogLevel::default(),
         }
     }
 }
@@ -63,7 +185,7 @@ impl AppSettings {
         }
 
         let content = tokio::fs::read_to_string(&settings_path).await?;
-        
+
         // 新しい形式で読み込み試行
         if let Ok(settings) = serde_json::from_str::<Self>(&content) {
             return Ok(settings);
@@ -103,11 +225,16 @@ impl AppSettings {
         }
 
         // 通知設定（旧形式のnotifications.enabledまたはnotifications_enabled）
-        if let Some(enabled) = legacy.get("notifications")
+        if let Some(enabled) = legacy
+            .get("notifications")
             .and_then(|n| n.get("enabled"))
-            .and_then(|v| v.as_bool()) {
+            .and_then(|v| v.as_bool())
+        {
             settings.notifications_enabled = enabled;
-        } else if let Some(enabled) = legacy.get("notifications_enabled").and_then(|v| v.as_bool()) {
+        } else if let Some(enabled) = legacy
+            .get("notifications_enabled")
+            .and_then(|v| v.as_bool())
+        {
             settings.notifications_enabled = enabled;
         }
 
@@ -127,6 +254,57 @@ impl AppSettings {
         Ok(settings)
     }
 
+    /// 保存前に値を検証（設定全体の保存・キーごとの更新で共通）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.polling_interval_ms < MIN_POLLING_INTERVAL_MS {
+            return Err(format!(
+                "polling_interval_msは{}以上である必要があります",
+                MIN_POLLING_INTERVAL_MS
+            ));
+        }
+        sensitive::validate_rules(&self.sensitive_rules)?;
+        for rule in &self.app_capture_rules {
+            rule.validate()?;
+        }
+        for rule in &self.capture_rules {
+            rule.validate()?;
+        }
+        size_limit::validate_limits(&self.content_size_limits)?;
+        Ok(())
+    }
+
+    /// 設定を取得（読み込み済みならファイルを読まずに返す）
+    ///
+    /// キャプチャ処理など頻繁に呼ばれる箇所で使う。設定の変更はすべてsave()を通るため、
+    /// 保存された内容がそのまま反映される。
+    pub async fn cached() -> Result<Self> {
+        if let Some(settings) = CACHE.lock().unwrap().clone() {
+            return Ok(settings);
+        }
+
+        let settings = Self::load().await?;
+        *CACHE.lock().unwrap() = Some(settings.clone());
+        Ok(settings)
+    }
+
+    /// 設定のルールをコンパイルしたものを取得（設定が保存されるまで同じものを使う）
+    ///
+    /// 設定を読み込めない場合はデフォルトの設定から作る。
+    pub async fn matchers() -> Arc<CaptureMatchers> {
+        if let Some(matchers) = MATCHERS.lock().unwrap().clone() {
+            return matchers;
+        }
+
+        match Self::cached().await {
+            Ok(settings) => {
+                let matchers = Arc::new(CaptureMatchers::new(&settings));
+                *MATCHERS.lock().unwrap() = Some(matchers.clone());
+                matchers
+            }
+            Err(_) => Arc::new(CaptureMatchers::new(&Self::default())),
+        }
+    }
+
     /// 設定ファイルに保存
     pub async fn save(&self) -> Result<()> {
         let settings_path = Self::get_settings_path()?;
@@ -138,6 +316,8 @@ impl AppSettings {
 
         let content = serde_json::to_string_pretty(self)?;
         tokio::fs::write(&settings_path, content).await?;
+        *CACHE.lock().unwrap() = Some(self.clone());
+        *MATCHERS.lock().unwrap() = Some(Arc::new(CaptureMatchers::new(self)));
 
         Ok(())
     }
@@ -153,21 +333,95 @@ pub async fn get_app_settings() -> Result<AppSettings, String> {
 
 /// アプリケーション設定を保存
 #[tauri::command]
-pub async fn save_app_settings(settings: AppSettings) -> Result<(), String> {
+pub async fn save_app_settings(app: AppHandle, settings: AppSettings) -> Result<(), String> {
+    settings.validate()?;
+
     settings
         .save()
         .await
-        .map_err(|e| format!("設定保存エラー: {}", e))
+        .map_err(|e| format!("設定保存エラー: {}", e))?;
+    logging::set_level(settings.log_level);
+    events::settings_changed(&app, None);
+    Ok(())
 }
 
-/// 設定を特定のキーで更新
-#[tauri::command]
-pub async fn update_setting(key: String, value: serde_json::Value) -> Result<(), String> {
+/// 設定を読み込んで変更し、検証してから保存する
+///
+/// キーごとの更新やルール一覧の編集など、設定の一部を変更するコマンドで共通。
+pub async fn modify_settings(
+    app: &AppHandle,
+    key: &str,
+    modify: impl FnOnce(&mut AppSettings) -> Result<(), String>,
+) -> Result<AppSettings, String> {
     let mut settings = AppSettings::load()
         .await
         .map_err(|e| format!("設定読み込みエラー: {}", e))?;
+    modify(&mut settings)?;
+    settings.validate()?;
+
+    settings
+        .save()
+        .await
+        .map_err(|e| format!("設定保存エラー: {}", e))?;
+    logging::set_level(settings.log_level);
+    events::settings_changed(app, Some(key));
+    Ok(settings)
+}
+
+/// IDで識別し、一覧の順に評価するルール（アプリ別キャプチャルール・キャプチャルール）
+pub trait SettingsRule {
+    fn id(&self) -> &str;
+    fn id_mut(&mut self) -> &mut String;
+}
+
+/// ルールを追加・更新（IDが空または未登録の場合は末尾に追加）
+pub fn upsert_rule<R: SettingsRule>(rules: &mut Vec<R>, mut rule: R) {
+    match rules
+        .iter_mut()
+        .find(|existing| !rule.id().is_empty() && existing.id() == rule.id())
+    {
+        Some(existing) => *existing = rule,
+        None => {
+            if rule.id().is_empty() {
+                *rule.id_mut() = Uuid::new_v4().to_string();
+            }
+            rules.push(rule);
+        }
+    }
+}
+
+/// ルールを削除
+pub fn delete_rule<R: SettingsRule>(rules: &mut Vec<R>, id: &str) {
+    rules.retain(|rule| rule.id() != id);
+}
+
+/// ルールの評価順を並べ替え（指定されなかったルールは末尾に残す）
+pub fn reorder_rules<R: SettingsRule>(rules: &mut [R], ids: &[String]) {
+    rules.sort_by_key(|rule| {
+        ids.iter()
+            .position(|id| id == rule.id())
+            .unwrap_or(usize::MAX)
+    });
+}
 
-    match key.as_str() {
+/// 設定を特定のキーで更新
+#[tauri::command]
+pub async fn update_setting(
+    app: AppHandle,
+    key: String,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    modify_settings(&app, &key, |settings| update_value(settings, &key, value)).await?;
+    Ok(())
+}
+
+/// 設定のキーに値を反映
+fn update_value(
+    settings: &mut AppSettings,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    match key {
         "max_history_items" => {
             settings.max_history_items = value
                 .as_u64()
@@ -191,23 +445,124 @@ pub async fn update_setting(key: String, value: serde_json::Value) -> Result<(),
                 .ok_or_else(|| "export_formatは文字列である必要があります".to_string())?
                 .to_string();
         }
+        "duplicate_policy" => {
+            settings.duplicate_policy = serde_json::from_value(value).map_err(|_| {
+                "duplicate_policyはkeep_all/move_to_top/ignoreのいずれかである必要があります"
+                    .to_string()
+            })?;
+        }
+        "ignore_whitespace_duplicates" => {
+            settings.ignore_whitespace_duplicates = value.as_bool().ok_or_else(|| {
+                "ignore_whitespace_duplicatesはboolean値である必要があります".to_string()
+            })?;
+        }
+        "blob_threshold_kb" => {
+            settings.blob_threshold_kb = value
+                .as_u64()
+                .ok_or_else(|| "blob_threshold_kbは数値である必要があります".to_string())?
+                as u32;
+        }
+        "concealed_policy" => {
+            settings.concealed_policy = serde_json::from_value(value).map_err(|_| {
+                "concealed_policyはskip/redactのいずれかである必要があります".to_string()
+            })?;
+        }
+        "concealed_placeholder_ttl_secs" => {
+            settings.concealed_placeholder_ttl_secs = value.as_u64().ok_or_else(|| {
+                "concealed_placeholder_ttl_secsは数値である必要があります".to_string()
+            })?;
+        }
+        "sensitive_detection_enabled" => {
+            settings.sensitive_detection_enabled = value.as_bool().ok_or_else(|| {
+                "sensitive_detection_enabledはboolean値である必要があります".to_string()
+            })?;
+        }
+        "sensitive_ttl_secs" => {
+            settings.sensitive_ttl_secs = value
+                .as_u64()
+                .ok_or_else(|| "sensitive_ttl_secsは数値である必要があります".to_string())?;
+        }
+        "sensitive_rules" => {
+            settings.sensitive_rules = serde_json::from_value(value)
+                .map_err(|e| format!("sensitive_rulesの形式が不正です: {}", e))?;
+        }
+        "monitor_on_startup" => {
+            settings.monitor_on_startup = value
+                .as_bool()
+                .ok_or_else(|| "monitor_on_startupはboolean値である必要があります".to_string())?;
+        }
+        "watcher_backend" => {
+            settings.watcher_backend = serde_json::from_value(value).map_err(|_| {
+                "watcher_backendはauto/event/pollingのいずれかである必要があります".to_string()
+            })?;
+        }
+        "polling_interval_ms" => {
+            settings.polling_interval_ms = value
+                .as_u64()
+                .ok_or_else(|| "polling_interval_msは数値である必要があります".to_string())?;
+        }
+        "primary_selection_enabled" => {
+            settings.primary_selection_enabled = value.as_bool().ok_or_else(|| {
+                "primary_selection_enabledはboolean値である必要があります".to_string()
+            })?;
+        }
+        "primary_selection_debounce_ms" => {
+            settings.primary_selection_debounce_ms = value.as_u64().ok_or_else(|| {
+                "primary_selection_debounce_msは数値である必要があります".to_string()
+            })?;
+        }
+        "extra_capture_formats" => {
+            settings.extra_capture_formats = serde_json::from_value(value).map_err(|_| {
+                "extra_capture_formatsは文字列の配列である必要があります".to_string()
+            })?;
+        }
+        "derive_markdown_from_html" => {
+            settings.derive_markdown_from_html = value.as_bool().ok_or_else(|| {
+                "derive_markdown_from_htmlはboolean値である必要があります".to_string()
+            })?;
+        }
+        "capture_coalesce_window_ms" => {
+            settings.capture_coalesce_window_ms = value.as_u64().ok_or_else(|| {
+                "capture_coalesce_window_msは数値である必要があります".to_string()
+            })?;
+        }
+        "capture_rate_limit_per_minute" => {
+            settings.capture_rate_limit_per_minute = value
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| {
+                    "capture_rate_limit_per_minuteは数値である必要があります".to_string()
+                })?;
+        }
+        "move_reused_item_to_top" => {
+            settings.move_reused_item_to_top = value.as_bool().ok_or_else(|| {
+                "move_reused_item_to_topはboolean値である必要があります".to_string()
+            })?;
+        }
+        "content_size_limits" => {
+            settings.content_size_limits = serde_json::from_value(value)
+                .map_err(|e| format!("content_size_limitsの形式が不正です: {}", e))?;
+        }
+        "log_level" => {
+            settings.log_level = serde_json::from_value(value).map_err(|_| {
+                "log_levelはerror/warn/info/debug/traceのいずれかである必要があります".to_string()
+            })?;
+        }
         _ => return Err(format!("未知の設定キー: {}", key)),
     }
-
-    settings
-        .save()
-        .await
-        .map_err(|e| format!("設定保存エラー: {}", e))
+    Ok(())
 }
 
 /// 設定をデフォルトにリセット
 #[tauri::command]
-pub async fn reset_settings() -> Result<AppSettings, String> {
+pub async fn reset_settings(app: AppHandle) -> Result<AppSettings, String> {
     let default_settings = AppSettings::default();
     default_settings
         .save()
         .await
         .map_err(|e| format!("設定リセットエラー: {}", e))?;
+    logging::set_level(default_settings.log_level);
+    events::settings_changed(&app, None);
 
     Ok(default_settings)
 }
@@ -244,3 +599,48 @@ pub async fn get_app_info() -> Result<AppInfo, String> {
         build_date,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Rule(String);
+
+    impl SettingsRule for Rule {
+        fn id(&self) -> &str {
+            &self.0
+        }
+
+        fn id_mut(&mut self) -> &mut String {
+            &mut self.0
+        }
+    }
+
+    fn rules(ids: &[&str]) -> Vec<Rule> {
+        ids.iter().map(|id| Rule(id.to_string())).collect()
+    }
+
+    #[test]
+    fn upsert_replaces_existing_and_appends_new_rules() {
+        let mut list = rules(&["a", "b"]);
+
+        upsert_rule(&mut list, Rule("b".to_string()));
+        upsert_rule(&mut list, Rule("c".to_string()));
+        assert_eq!(list, rules(&["a", "b", "c"]));
+
+        upsert_rule(&mut list, Rule(String::new()));
+        assert_eq!(list.len(), 4);
+        assert!(!list[3].id().is_empty());
+    }
+
+    #[test]
+    fn reorder_keeps_unlisted_rules_at_the_end() {
+        let mut list = rules(&["a", "b", "c"]);
+        reorder_rules(&mut list, &["c".to_string(), "a".to_string()]);
+        assert_eq!(list, rules(&["c", "a", "b"]));
+
+        delete_rule(&mut list, "a");
+        assert_eq!(list, rules(&["c", "b"]));
+    }
+}
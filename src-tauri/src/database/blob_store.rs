@@ -0,0 +1,86 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// コンテンツアドレス方式のBLOBストア
+///
+/// 大きなペイロードを `<データベースディレクトリ>/blobs/<ハッシュ先頭2文字>/<ハッシュ>` に保存する。
+/// 同じ内容は同じファイルを共有し、参照数はデータベースの `blobs` テーブルで管理する。
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+impl BlobStore {
+    /// データベースファイルと同じディレクトリにBLOBストアを作成
+    pub async fn open(db_path: &Path) -> Result<Self> {
+        let root = db_path
+            .parent()
+            .map(|parent| parent.join("blobs"))
+            .ok_or_else(|| anyhow::anyhow!("BLOBストアの保存先を決定できません"))?;
+        tokio::fs::create_dir_all(&root).await?;
+
+        Ok(Self { root })
+    }
+
+    /// ハッシュに対応するファイルパス
+    fn path_for(&self, hash: &str) -> PathBuf {
+        let prefix = hash.get(..2).unwrap_or("00");
+        self.root.join(prefix).join(hash)
+    }
+
+    /// BLOBを書き込む（同じ内容が既に存在する場合は何もしない）
+    pub async fn put(&self, hash: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.path_for(hash);
+        if tokio::fs::try_exists(&path).await? {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // 書き込み途中のファイルを参照しないよう一時ファイル経由で配置
+        let temp_path = path.with_extension("tmp");
+        tokio::fs::write(&temp_path, bytes).await?;
+        tokio::fs::rename(&temp_path, &path).await?;
+
+        Ok(())
+    }
+
+    /// BLOBを読み込む
+    pub async fn get(&self, hash: &str) -> Result<Vec<u8>> {
+        tokio::fs::read(self.path_for(hash))
+            .await
+            .map_err(|e| anyhow::anyhow!("BLOB読み込みエラー ({}): {}", hash, e))
+    }
+
+    /// BLOBを削除する（存在しない場合は何もしない）
+    pub async fn remove(&self, hash: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path_for(hash)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 保存されている全BLOBのハッシュを列挙（孤立ファイルの掃除用）
+    pub async fn list(&self) -> Result<Vec<String>> {
+        let mut hashes = Vec::new();
+
+        let mut prefixes = tokio::fs::read_dir(&self.root).await?;
+        while let Some(prefix) = prefixes.next_entry().await? {
+            if !prefix.file_type().await?.is_dir() {
+                continue;
+            }
+
+            let mut entries = tokio::fs::read_dir(prefix.path()).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                if let Some(name) = entry.file_name().to_str() {
+                    hashes.push(name.to_string());
+                }
+            }
+        }
+
+        Ok(hashes)
+    }
+}
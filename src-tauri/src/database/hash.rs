@@ -0,0 +1,29 @@
+use sha2::{Digest, Sha256};
+
+/// 重複判定用にコンテンツを正規化
+///
+/// 改行コードをLFに統一し、前後の空白を除去する。
+/// `ignore_whitespace` が有効な場合は連続する空白文字を1つの空白にまとめる。
+pub fn normalize_content(content: &str, ignore_whitespace: bool) -> String {
+    if ignore_whitespace {
+        return content.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+
+    content.replace("\r\n", "\n").trim().to_string()
+}
+
+/// 正規化済みコンテンツのSHA-256ハッシュ（16進文字列）を計算
+pub fn content_hash(content: &str, ignore_whitespace: bool) -> String {
+    let normalized = normalize_content(content, ignore_whitespace);
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+/// ログ出力用のコンテンツの要約（内容そのものは出力せず、サイズとハッシュの先頭部分のみ）
+pub fn log_summary(size: usize, hash: &str) -> String {
+    format!("{}バイト, ハッシュ: {}", size, &hash[..hash.len().min(12)])
+}
+
+/// バイナリデータのSHA-256ハッシュ（16進文字列）を計算
+pub fn binary_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
@@ -1,13 +1,50 @@
 use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{migrate::Migrator, sqlite::SqlitePool, Row};
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{
+        Sqlite, SqliteAutoVacuum, SqliteConnectOptions, SqliteConnection, SqliteJournalMode,
+        SqlitePool, SqlitePoolOptions, SqliteRow, SqliteSynchronous,
+    },
+    Executor, Row,
+};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+pub mod blob_store;
+pub mod hash;
+
+use crate::capture::classifier::{self, Classification};
+use crate::capture::markup;
+use blob_store::BlobStore;
+
 // SQLx標準マイグレーション
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
+/// 接続プールの最大接続数（読み込みは書き込みと並行して実行される）
+const MAX_CONNECTIONS: u32 = 8;
+
+/// 他の接続が書き込み中の場合に待つ最大時間
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// クリーンアップ時に1回で解放する空きページ数の上限（書き込みを長く止めないように）
+const INCREMENTAL_VACUUM_PAGES: u32 = 2048;
+
+/// BLOBストアに退避するサイズのデフォルト閾値（バイト）
+///
+/// 一覧表示ではこれを超えるテキストをBLOBストアから読み込まず、先頭部分のみ返す。
+pub const DEFAULT_BLOB_THRESHOLD: usize = 256 * 1024;
+
+/// BLOBストアに退避したテキストのうち、検索・プレビュー用にデータベースに残す先頭部分（バイト）
+const BLOB_TEXT_INDEX_BYTES: usize = 16 * 1024;
+
 /// 正規化されたクリップボードアイテムの構造体
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardItem {
@@ -16,8 +53,23 @@ pub struct ClipboardItem {
     pub timestamp: i64,
     pub is_favorite: bool,
     pub source_app: Option<String>,
+    pub source_executable: Option<String>,
+    pub source_window_title: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub content_hash: Option<String>,
+    pub copy_count: i64,
+    pub last_used_at: Option<i64>, // ClipOneから最後に書き戻した時刻（Unixミリ秒）
+    pub use_count: i64,            // ClipOneから書き戻した回数
+    pub expires_at: Option<i64>,   // この時刻（Unixミリ秒）を過ぎると自動削除
+    pub is_sensitive: bool,
+    pub sensitive_kind: Option<String>, // 一致した機密データ検出ルール
+    pub selection: String,              // 取得元のセレクション（clipboard / primary）
+    pub kind: Option<String>,           // テキストの内容の種類（url / json / code など）
+    pub kind_confidence: Option<f64>,   // 種類の判定の確信度（0.0〜1.0）
+    pub kind_detail: Option<String>,    // 種類の補足情報（コードの言語など）
     pub contents: Vec<ClipboardContent>,
+    pub files: Vec<FileEntry>, // ファイル・ファイルパスの場合の各ファイルの情報
+    pub tags: Vec<String>,
 }
 
 /// クリップボードコンテンツの構造体（正規化されたテーブル用）
@@ -26,10 +78,200 @@ pub struct ClipboardContent {
     pub item_id: String,
     pub format: String,
     pub content: String,
+    #[serde(skip)]
+    pub content_blob: Option<Vec<u8>>, // 画像などのバイナリ形式
+    #[serde(skip)]
+    pub blob_ref: Option<String>, // BLOBストアに退避した場合のハッシュ
+    #[serde(skip)]
+    pub blob_kind: Option<String>, // 退避したデータの種類（text / binary）
     pub data_size: i64,
+    pub content_hash: Option<String>,
+    pub image_metadata: Option<ImageMetadata>,
+    pub is_derived: bool, // HTML・RTFから派生させた形式（元のアプリが提供したものではない）
+    pub is_truncated: bool, // サイズ上限により切り詰めた
+    pub original_size: Option<i64>, // 切り詰める前のサイズ（バイト）
+    #[serde(skip)]
+    pub is_partial: bool, // 一覧表示用に先頭部分のみ読み込んだ
     pub created_at: DateTime<Utc>,
 }
 
+/// 画像形式のメタデータ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    pub pixel_format: String, // 例: Rgba8
+    pub byte_size: i64,       // エンコード済みデータの実サイズ
+    pub thumbnail_width: Option<u32>,
+    pub thumbnail_height: Option<u32>,
+}
+
+/// ファイル・ファイルリストに含まれるファイルの情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub path: String, // 絶対パス
+    pub exists: bool,
+    pub is_dir: bool,
+    pub size: Option<i64>, // バイト数（ディレクトリ・存在しない場合はNone）
+    pub modified_at: Option<i64>, // 更新日時（Unixミリ秒）
+    pub mime_type: Option<String>, // 先頭バイト列・拡張子から判定した種類
+    pub checked_at: i64,   // 最後に確認した時刻（Unixミリ秒）
+}
+
+/// 取得時に生成した画像のメタデータとサムネイル
+#[derive(Debug, Clone)]
+pub struct CapturedImage {
+    pub metadata: ImageMetadata,
+    pub thumbnail: Vec<u8>, // PNG
+}
+
+/// コピー元アプリケーションの情報（検出できない項目はNone）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceApp {
+    pub name: Option<String>,         // アプリ名（例: WM_CLASS）
+    pub executable: Option<String>,   // 実行ファイルのパス
+    pub window_title: Option<String>, // コピー時のウィンドウタイトル
+}
+
+/// 取得元のセレクション
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardSelection {
+    /// 通常のクリップボード（Ctrl+C）
+    #[default]
+    Clipboard,
+    /// X11のPRIMARYセレクション（テキスト選択・中クリック貼り付け）
+    Primary,
+}
+
+impl ClipboardSelection {
+    /// データベースに保存する値
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClipboardSelection::Clipboard => "clipboard",
+            ClipboardSelection::Primary => "primary",
+        }
+    }
+}
+
+/// 新規保存するクリップボードアイテム
+#[derive(Debug, Clone, Default)]
+pub struct NewClipboardItem {
+    pub primary_format: String,
+    pub source: SourceApp,
+    pub format_contents: HashMap<String, String>, // テキスト形式
+    pub derived_formats: HashSet<String>, // format_contentsのうちHTML・RTFから派生させた形式
+    pub binary_contents: HashMap<String, Vec<u8>>, // 画像などのバイナリ形式
+    pub images: HashMap<String, CapturedImage>, // 画像形式のメタデータとサムネイル
+    pub blob_threshold: Option<usize>, // これを超えるコンテンツはBLOBストアに退避（未指定はデフォルト）
+    pub expires_at: Option<i64>,       // 自動削除する時刻（Unixミリ秒）
+    pub sensitive_kind: Option<String>, // 機密データとして検出された場合の種類
+    pub selection: ClipboardSelection,
+    pub classification: Option<Classification>, // テキストの内容の分類結果
+    pub files: Vec<FileEntry>,                  // ファイル・ファイルパスの場合の各ファイルの情報
+    pub tags: Vec<String>,                      // キャプチャルールで付けたタグ
+    pub is_favorite: bool,                      // キャプチャルールでお気に入りにする
+    pub truncated_formats: HashMap<String, usize>, // サイズ上限により切り詰めた形式と元のサイズ
+    pub offloaded_formats: HashSet<String>,     // サイズ上限によりBLOBストアに退避する形式
+}
+
+impl NewClipboardItem {
+    /// 単一形式のコンテンツからアイテムを作成（画像のdata URLはBLOBとして保存する）
+    pub fn from_single_content(
+        content: &str,
+        content_type: &str,
+        source_app: Option<&str>,
+    ) -> Self {
+        let mut item = NewClipboardItem {
+            primary_format: content_type.to_string(),
+            source: SourceApp {
+                name: source_app.map(str::to_string),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        match decode_data_url(content) {
+            Some((mime, bytes)) if mime.starts_with("image/") => {
+                if let Ok(image) = crate::capture::imaging::analyze_encoded(&bytes) {
+                    item.images.insert(content_type.to_string(), image);
+                }
+                item.binary_contents.insert(content_type.to_string(), bytes);
+            }
+            _ => {
+                item.format_contents
+                    .insert(content_type.to_string(), content.to_string());
+                markup::add_derived_formats(&mut item, false);
+                item.classify();
+                item.files = crate::capture::files::resolve_item(&item);
+            }
+        }
+        item
+    }
+
+    /// プライマリコンテンツのハッシュ（重複判定用）
+    pub fn content_hash(&self, ignore_whitespace: bool) -> String {
+        if let Some(bytes) = self.binary_contents.get(&self.primary_format) {
+            return hash::binary_hash(bytes);
+        }
+
+        hash::content_hash(self.primary_text(), ignore_whitespace)
+    }
+
+    /// ログ出力用のプライマリコンテンツの要約（内容そのものは含めない）
+    pub fn log_summary(&self) -> String {
+        let size = match self.binary_contents.get(&self.primary_format) {
+            Some(bytes) => bytes.len(),
+            None => self.primary_text().len(),
+        };
+        hash::log_summary(size, &self.content_hash(false))
+    }
+
+    /// プライマリ形式のテキストを分類する（テキスト形式でない場合は分類しない）
+    pub fn classify(&mut self) {
+        self.classification = self
+            .format_contents
+            .get(&self.primary_format)
+            .filter(|_| self.primary_format.starts_with("text/"))
+            .map(|text| classifier::classify(text));
+    }
+
+    /// プライマリ形式のテキスト（テキスト形式でない場合は空文字列）
+    fn primary_text(&self) -> &str {
+        self.format_contents
+            .get(&self.primary_format)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// UTF-8の文字境界を考慮して先頭max_bytesバイト以内に切り詰める
+pub fn truncate_to_char_boundary(text: &str, max_bytes: usize) -> &str {
+    let mut boundary = max_bytes.min(text.len());
+    while boundary > 0 && !text.is_char_boundary(boundary) {
+        boundary -= 1;
+    }
+    &text[..boundary]
+}
+
+/// コンテンツの読み込み方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentLoad {
+    /// 画像は元データを読み込む
+    Full,
+    /// 画像はサムネイルで代替する（一覧表示用）
+    Preview,
+}
+
+impl ClipboardContent {
+    /// 表示用の文字列（バイナリ形式はdata URLに変換）
+    pub fn display_content(&self) -> String {
+        match &self.content_blob {
+            Some(blob) => format!("data:{};base64,{}", self.format, BASE64.encode(blob)),
+            None => self.content.clone(),
+        }
+    }
+}
+
 /// 旧形式との互換性のためのDisplayClipboardItem（フロントエンド用）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisplayClipboardItem {
@@ -39,14 +281,198 @@ pub struct DisplayClipboardItem {
     pub timestamp: i64,
     pub is_favorite: bool,
     pub source_app: Option<String>,
+    #[serde(default)]
+    pub source_executable: Option<String>,
+    #[serde(default)]
+    pub source_window_title: Option<String>,
     pub created_at: DateTime<Utc>,
     pub available_formats: Option<Vec<String>>,
     pub format_contents: Option<std::collections::HashMap<String, String>>,
+    #[serde(default = "default_copy_count")]
+    pub copy_count: i64,
+    #[serde(default)]
+    pub last_used_at: Option<i64>,
+    #[serde(default)]
+    pub use_count: i64,
+    #[serde(default)]
+    pub image_metadata: Option<ImageMetadata>,
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    #[serde(default)]
+    pub is_sensitive: bool,
+    #[serde(default)]
+    pub sensitive_kind: Option<String>,
+    #[serde(default = "default_selection")]
+    pub selection: String,
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub kind_confidence: Option<f64>,
+    #[serde(default)]
+    pub kind_detail: Option<String>,
+    #[serde(default)]
+    pub files: Vec<FileEntry>,
+    #[serde(default)]
+    pub derived_formats: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub truncated_formats: HashMap<String, i64>, // サイズ上限により切り詰めた形式と元のサイズ
+    #[serde(default)]
+    pub partial_formats: Vec<String>, // 先頭部分のみ含む形式（全体は個別に取得する）
+}
+
+fn default_selection() -> String {
+    ClipboardSelection::Clipboard.as_str().to_string()
+}
+
+fn default_copy_count() -> i64 {
+    1
+}
+
+impl From<ClipboardItem> for DisplayClipboardItem {
+    /// 機密データとして検出されたアイテムはテキストの内容を伏せて変換する
+    fn from(item: ClipboardItem) -> Self {
+        let mask = item.is_sensitive;
+        Self::convert(item, mask)
+    }
+}
+
+impl DisplayClipboardItem {
+    /// 機密データも伏せずに変換する（コピーなどで元の内容が必要な場合）
+    pub fn revealed(item: ClipboardItem) -> Self {
+        Self::convert(item, false)
+    }
+
+    fn convert(item: ClipboardItem, mask: bool) -> Self {
+        let available_formats: Vec<String> =
+            item.contents.iter().map(|c| c.format.clone()).collect();
+        let derived_formats: Vec<String> = item
+            .contents
+            .iter()
+            .filter(|c| c.is_derived)
+            .map(|c| c.format.clone())
+            .collect();
+        let truncated_formats: HashMap<String, i64> = item
+            .contents
+            .iter()
+            .filter(|c| c.is_truncated)
+            .map(|c| (c.format.clone(), c.original_size.unwrap_or(c.data_size)))
+            .collect();
+        let partial_formats: Vec<String> = item
+            .contents
+            .iter()
+            .filter(|c| c.is_partial)
+            .map(|c| c.format.clone())
+            .collect();
+        let format_contents: std::collections::HashMap<String, String> = item
+            .contents
+            .iter()
+            .map(|c| {
+                let content = if mask && c.content_blob.is_none() {
+                    crate::capture::sensitive::mask(&c.content)
+                } else {
+                    c.display_content()
+                };
+                (c.format.clone(), content)
+            })
+            .collect();
+
+        // プライマリコンテンツを取得
+        let primary_content = format_contents
+            .get(&item.primary_format)
+            .cloned()
+            .unwrap_or_else(|| "[No content]".to_string());
+        let image_metadata = item
+            .contents
+            .iter()
+            .find(|c| c.format == item.primary_format)
+            .and_then(|c| c.image_metadata.clone());
+
+        DisplayClipboardItem {
+            id: item.id,
+            content: primary_content,
+            content_type: item.primary_format,
+            timestamp: item.timestamp,
+            is_favorite: item.is_favorite,
+            source_app: item.source_app,
+            source_executable: item.source_executable,
+            source_window_title: item.source_window_title,
+            created_at: item.created_at,
+            available_formats: Some(available_formats),
+            format_contents: Some(format_contents),
+            copy_count: item.copy_count,
+            last_used_at: item.last_used_at,
+            use_count: item.use_count,
+            image_metadata,
+            expires_at: item.expires_at,
+            is_sensitive: item.is_sensitive,
+            sensitive_kind: item.sensitive_kind,
+            selection: item.selection,
+            kind: item.kind,
+            kind_confidence: item.kind_confidence,
+            kind_detail: item.kind_detail,
+            files: item.files,
+            derived_formats,
+            tags: item.tags,
+            truncated_formats,
+            partial_formats,
+        }
+    }
+}
+
+/// base64のdata URLをMIMEタイプとバイト列にデコード
+fn decode_data_url(content: &str) -> Option<(&str, Vec<u8>)> {
+    let (header, encoded) = content.strip_prefix("data:")?.split_once(";base64,")?;
+    let bytes = BASE64.decode(encoded).ok()?;
+    Some((header, bytes))
+}
+
+/// clipboard_itemsテーブルの取得カラム
+const ITEM_COLUMNS: &str =
+    "id, primary_format, timestamp, is_favorite, source_app, source_executable, \
+     source_window_title, created_at, content_hash, copy_count, last_used_at, use_count, \
+     expires_at, is_sensitive, sensitive_kind, selection, kind, kind_confidence, kind_detail";
+
+/// clipboard_itemsの行からアイテムを組み立てる
+fn item_from_row(
+    row: &SqliteRow,
+    contents: Vec<ClipboardContent>,
+    files: Vec<FileEntry>,
+    tags: Vec<String>,
+) -> ClipboardItem {
+    ClipboardItem {
+        id: row.get("id"),
+        primary_format: row.get("primary_format"),
+        timestamp: row.get("timestamp"),
+        is_favorite: row.get("is_favorite"),
+        source_app: row.get("source_app"),
+        source_executable: row.get("source_executable"),
+        source_window_title: row.get("source_window_title"),
+        created_at: row.get("created_at"),
+        content_hash: row.get("content_hash"),
+        copy_count: row.get("copy_count"),
+        last_used_at: row.get("last_used_at"),
+        use_count: row.get("use_count"),
+        expires_at: row.get("expires_at"),
+        is_sensitive: row.get("is_sensitive"),
+        sensitive_kind: row.get("sensitive_kind"),
+        selection: row.get("selection"),
+        kind: row.get("kind"),
+        kind_confidence: row.get("kind_confidence"),
+        kind_detail: row.get("kind_detail"),
+        contents,
+        files,
+        tags,
+    }
 }
 
 /// データベース接続とマイグレーション管理
 pub struct Database {
     pool: SqlitePool,
+    blobs: BlobStore,
+    // BLOBの保存と未参照BLOBの削除が入れ違わないようにする（参照数0の行を削除しないように）
+    blob_lock: Mutex<()>,
 }
 
 impl Database {
@@ -54,18 +480,18 @@ impl Database {
     pub async fn new() -> Result<Self> {
         let db_path = Self::get_database_path().await?;
 
-        println!("データベースパス: {}", db_path.display());
+        info!("データベースパス: {}", db_path.display());
 
         // データベースディレクトリを作成
         if let Some(parent) = db_path.parent() {
-            println!("ディレクトリ作成: {}", parent.display());
+            debug!("ディレクトリ作成: {}", parent.display());
             tokio::fs::create_dir_all(parent).await?;
 
             // ディレクトリが実際に作成されたか確認
             if parent.exists() {
-                println!("ディレクトリ作成成功: {}", parent.display());
+                debug!("ディレクトリ作成成功: {}", parent.display());
             } else {
-                println!("ディレクトリ作成失敗: {}", parent.display());
+                warn!("ディレクトリ作成失敗: {}", parent.display());
             }
         }
 
@@ -78,36 +504,42 @@ impl Database {
         } else {
             format!("sqlite://{}", db_path.display())
         };
-        println!("データベースURL: {}", database_url);
-
-        // 空のファイルを事前作成してみる
-        if !db_path.exists() {
-            println!("データベースファイル事前作成: {}", db_path.display());
-            if let Err(e) = tokio::fs::File::create(&db_path).await {
-                println!("ファイル作成エラー: {}", e);
-                return Err(anyhow::anyhow!("ファイル作成エラー: {}", e));
-            }
-        }
+        debug!("データベースURL: {}", database_url);
 
-        let pool = match SqlitePool::connect(&database_url).await {
+        // WALモードで開き、読み込みが書き込みを待たないようにする
+        let options = SqliteConnectOptions::from_str(&database_url)?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT)
+            .foreign_keys(true)
+            .auto_vacuum(SqliteAutoVacuum::Incremental)
+            .pragma("temp_store", "memory")
+            .pragma("cache_size", "-16000"); // 約16MB
+
+        let pool = match SqlitePoolOptions::new()
+            .max_connections(MAX_CONNECTIONS)
+            .connect_with(options)
+            .await
+        {
             Ok(pool) => {
-                println!("データベース接続成功");
+                info!("データベース接続成功");
                 pool
             }
             Err(e) => {
-                println!("データベース接続エラー詳細: {:?}", e);
+                error!("データベース接続エラー詳細: {:?}", e);
                 return Err(anyhow::anyhow!("データベース接続エラー: {}", e));
             }
         };
 
         // SQLx標準マイグレーション実行
-        println!("🚀 SQLxマイグレーション実行中...");
+        info!("SQLxマイグレーション実行中...");
         match MIGRATOR.run(&pool).await {
             Ok(_) => {
-                println!("✅ SQLxマイグレーション完了");
+                info!("SQLxマイグレーション完了");
             }
             Err(e) => {
-                println!("❌ SQLxマイグレーションエラー: {}", e);
+                error!("SQLxマイグレーションエラー: {}", e);
                 return Err(anyhow::anyhow!("マイグレーション失敗: {}", e));
             }
         }
@@ -118,13 +550,399 @@ impl Database {
         )
         .fetch_one(&pool)
         .await?;
-        println!("📈 clipboard_itemsテーブルの存在チェック: {}", table_check);
+        debug!("clipboard_itemsテーブルの存在チェック: {}", table_check);
+
+        let blobs = BlobStore::open(&db_path).await?;
+        let db = Self {
+            pool,
+            blobs,
+            blob_lock: Mutex::new(()),
+        };
+
+        // data URL形式で保存された既存の画像をBLOBへ変換
+        db.migrate_data_url_images().await?;
+
+        // ハッシュ未計算の既存データを補完
+        db.backfill_content_hashes().await?;
+
+        // メタデータ未生成の既存画像を補完
+        db.backfill_image_metadata().await?;
+
+        // テキストのない既存のHTML・RTFからテキストを派生
+        db.backfill_derived_texts().await?;
 
-        let db = Self { pool };
+        // 種類が未判定の既存テキストを分類
+        db.backfill_content_kinds().await?;
+
+        // 停止中に有効期限を過ぎたアイテムを削除
+        db.delete_expired_items().await?;
+
+        // 参照されていないBLOBファイルを掃除
+        db.sweep_orphan_blobs().await?;
+
+        // 既存のデータベースを少しずつ空き領域を解放できる形式に変換
+        db.enable_incremental_vacuum().await?;
 
         Ok(db)
     }
 
+    /// auto_vacuumがINCREMENTALでない既存のデータベースを変換（起動時に1回だけVACUUMする）
+    async fn enable_incremental_vacuum(&self) -> Result<()> {
+        let mode: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+            .fetch_one(&self.pool)
+            .await?;
+        if mode == 2 {
+            return Ok(());
+        }
+
+        info!("データベースをINCREMENTAL auto_vacuumに変換中...");
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("VACUUM").execute(&mut *conn).await?;
+        info!("データベースの変換完了");
+
+        Ok(())
+    }
+
+    /// data URL（base64テキスト）で保存された画像をBLOBに変換
+    async fn migrate_data_url_images(&self) -> Result<()> {
+        let rows = sqlx::query(
+            "SELECT item_id, format, content FROM clipboard_contents
+             WHERE content_blob IS NULL AND content LIKE 'data:image/%;base64,%'",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        info!("data URL形式の画像をBLOBに変換中: {}件", rows.len());
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            let item_id: String = row.get("item_id");
+            let format: String = row.get("format");
+            let content: String = row.get("content");
+
+            let Some((_, bytes)) = decode_data_url(&content) else {
+                warn!("画像データのデコードに失敗: {}", item_id);
+                continue;
+            };
+            let blob_hash = hash::binary_hash(&bytes);
+
+            sqlx::query(
+                "UPDATE clipboard_contents SET content = '', content_blob = ?, data_size = ?, content_hash = ?
+                 WHERE item_id = ? AND format = ?",
+            )
+            .bind(&bytes)
+            .bind(bytes.len() as i64)
+            .bind(&blob_hash)
+            .bind(&item_id)
+            .bind(&format)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                "UPDATE clipboard_items SET content_hash = ?, loose_content_hash = ?
+                 WHERE id = ? AND primary_format = ?",
+            )
+            .bind(&blob_hash)
+            .bind(&blob_hash)
+            .bind(&item_id)
+            .bind(&format)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// ハッシュ未計算のアイテムとコンテンツにハッシュを設定
+    async fn backfill_content_hashes(&self) -> Result<()> {
+        let rows = sqlx::query(
+            "SELECT item_id, format, content FROM clipboard_contents WHERE content_hash IS NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        info!("既存データのコンテンツハッシュを計算中: {}件", rows.len());
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            let item_id: String = row.get("item_id");
+            let format: String = row.get("format");
+            let content: String = row.get("content");
+
+            sqlx::query(
+                "UPDATE clipboard_contents SET content_hash = ? WHERE item_id = ? AND format = ?",
+            )
+            .bind(hash::content_hash(&content, false))
+            .bind(&item_id)
+            .bind(&format)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                "UPDATE clipboard_items SET content_hash = ?, loose_content_hash = ?
+                 WHERE id = ? AND primary_format = ? AND content_hash IS NULL",
+            )
+            .bind(hash::content_hash(&content, false))
+            .bind(hash::content_hash(&content, true))
+            .bind(&item_id)
+            .bind(&format)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// テキスト形式のない既存のHTML・RTFアイテムにプレーンテキストを派生させる
+    async fn backfill_derived_texts(&self) -> Result<()> {
+        let rows = sqlx::query(
+            "SELECT cc.item_id, cc.format, cc.content FROM clipboard_contents cc
+             WHERE cc.format IN ('text/html', 'text/rtf') AND cc.content_blob IS NULL
+               AND NOT EXISTS (
+                 SELECT 1 FROM clipboard_contents other
+                 WHERE other.item_id = cc.item_id AND other.format = 'text/plain'
+               )
+             ORDER BY cc.item_id, cc.format",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        info!("既存のHTML・RTFからテキストを派生中: {}件", rows.len());
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            let item_id: String = row.get("item_id");
+            let format: String = row.get("format");
+            let content: String = row.get("content");
+            let text = match format.as_str() {
+                "text/html" => markup::html_to_text(&content),
+                _ => markup::rtf_to_text(&content),
+            };
+            if text.is_empty() {
+                continue;
+            }
+
+            // HTMLとRTFの両方がある場合はHTML（先に処理される）を優先
+            sqlx::query(
+                "INSERT OR IGNORE INTO clipboard_contents (item_id, format, content, data_size, content_hash, is_derived, created_at)
+                 VALUES (?, 'text/plain', ?, ?, ?, TRUE, ?)",
+            )
+            .bind(&item_id)
+            .bind(&text)
+            .bind(text.len() as i64)
+            .bind(hash::content_hash(&text, false))
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// 種類が未判定の既存テキストアイテムを分類
+    async fn backfill_content_kinds(&self) -> Result<()> {
+        let rows = sqlx::query(
+            "SELECT ci.id, cc.content FROM clipboard_items ci
+             JOIN clipboard_contents cc ON cc.item_id = ci.id AND cc.format = ci.primary_format
+             WHERE ci.kind IS NULL AND ci.primary_format LIKE 'text/%'
+               AND cc.content_blob IS NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        info!("既存データの種類を判定中: {}件", rows.len());
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            let id: String = row.get("id");
+            let content: String = row.get("content");
+            let classification = classifier::classify(&content);
+
+            sqlx::query(
+                "UPDATE clipboard_items SET kind = ?, kind_confidence = ?, kind_detail = ? WHERE id = ?",
+            )
+            .bind(classification.kind.as_str())
+            .bind(classification.confidence)
+            .bind(&classification.detail)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// メタデータ・サムネイル未生成の既存画像を補完
+    async fn backfill_image_metadata(&self) -> Result<()> {
+        let rows = sqlx::query(
+            "SELECT cc.item_id, cc.format, cc.content_blob
+             FROM clipboard_contents cc
+             LEFT JOIN clipboard_images ci ON ci.item_id = cc.item_id AND ci.format = cc.format
+             WHERE cc.content_blob IS NOT NULL AND cc.format LIKE 'image/%' AND ci.item_id IS NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        info!("既存画像のサムネイルを生成中: {}件", rows.len());
+        for row in rows {
+            let item_id: String = row.get("item_id");
+            let format: String = row.get("format");
+            let blob: Vec<u8> = row.get("content_blob");
+
+            match crate::capture::imaging::analyze_encoded(&blob) {
+                Ok(image) => {
+                    Self::save_image_metadata(&self.pool, &item_id, &format, &image).await?
+                }
+                Err(e) => warn!("サムネイル生成に失敗: {} ({})", item_id, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 参照数が0のBLOBと、データベースに登録されていないBLOBファイルを削除
+    async fn sweep_orphan_blobs(&self) -> Result<()> {
+        self.collect_garbage_blobs().await?;
+
+        let known: HashSet<String> = sqlx::query_scalar("SELECT hash FROM blobs")
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .collect();
+
+        for hash in self.blobs.list().await? {
+            if !known.contains(&hash) {
+                info!("孤立したBLOBファイルを削除: {}", hash);
+                self.blobs.remove(&hash).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 参照数が0になったBLOBを削除
+    async fn collect_garbage_blobs(&self) -> Result<()> {
+        let _guard = self.blob_lock.lock().await;
+        let hashes: Vec<String> =
+            sqlx::query_scalar("DELETE FROM blobs WHERE ref_count <= 0 RETURNING hash")
+                .fetch_all(&self.pool)
+                .await?;
+
+        // ファイル削除に失敗しても次回起動時の掃除で削除される
+        for hash in &hashes {
+            if let Err(e) = self.blobs.remove(hash).await {
+                error!("BLOBファイル削除エラー: {} ({})", hash, e);
+            }
+        }
+
+        if !hashes.is_empty() {
+            info!("未参照のBLOBを削除: {}件", hashes.len());
+        }
+
+        Ok(())
+    }
+
+    /// BLOBストアにデータを保存し、参照用のハッシュを返す
+    async fn store_blob<'c>(
+        &self,
+        executor: impl Executor<'c, Database = Sqlite>,
+        bytes: &[u8],
+    ) -> Result<String> {
+        let blob_hash = hash::binary_hash(bytes);
+
+        // 同じ内容のBLOBは共有する（参照数はトリガーで管理）
+        sqlx::query("INSERT INTO blobs (hash, size) VALUES (?, ?) ON CONFLICT(hash) DO NOTHING")
+            .bind(&blob_hash)
+            .bind(bytes.len() as i64)
+            .execute(executor)
+            .await?;
+        self.blobs.put(&blob_hash, bytes).await?;
+
+        Ok(blob_hash)
+    }
+
+    /// BLOBストアに退避したコンテンツを読み込む
+    async fn load_blob_content(&self, content: &mut ClipboardContent, load: ContentLoad) {
+        let Some(blob_ref) = content.blob_ref.as_deref() else {
+            return;
+        };
+
+        // 一覧表示では大きなテキストは読み込まず、データベースに残した先頭部分を返す
+        let is_text = content.blob_kind.as_deref() == Some("text");
+        if is_text
+            && load == ContentLoad::Preview
+            && content.data_size > DEFAULT_BLOB_THRESHOLD as i64
+        {
+            content.is_partial = true;
+            return;
+        }
+
+        // 一覧表示でサムネイルに置き換え済みの画像、画像以外のバイナリ形式は読み込まない
+        if !is_text
+            && (content.content_blob.is_some()
+                || (load == ContentLoad::Preview && !content.format.starts_with("image/")))
+        {
+            return;
+        }
+
+        match self.blobs.get(blob_ref).await {
+            Ok(bytes) if is_text => content.content = String::from_utf8_lossy(&bytes).into_owned(),
+            Ok(bytes) => content.content_blob = Some(bytes),
+            Err(e) => warn!("{}", e),
+        }
+    }
+
+    /// 画像のメタデータとサムネイルを保存
+    async fn save_image_metadata<'c>(
+        executor: impl Executor<'c, Database = Sqlite>,
+        item_id: &str,
+        format: &str,
+        image: &CapturedImage,
+    ) -> Result<()> {
+        let metadata = &image.metadata;
+        sqlx::query(
+            "INSERT OR REPLACE INTO clipboard_images
+             (item_id, format, width, height, pixel_format, byte_size, thumbnail, thumbnail_width, thumbnail_height)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(item_id)
+        .bind(format)
+        .bind(metadata.width)
+        .bind(metadata.height)
+        .bind(&metadata.pixel_format)
+        .bind(metadata.byte_size)
+        .bind(&image.thumbnail)
+        .bind(metadata.thumbnail_width)
+        .bind(metadata.thumbnail_height)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
     /// データベースファイルのパスを取得
     async fn get_database_path() -> Result<PathBuf> {
         // プロジェクトルートのdataディレクトリを使用（src-tauriの外）
@@ -136,44 +954,120 @@ impl Database {
     /// 正規化されたデータベースでクリップボードアイテムとコンテンツを保存
     pub async fn save_clipboard_item_normalized(
         &self,
-        primary_format: &str,
-        source_app: Option<&str>,
-        format_contents: &std::collections::HashMap<String, String>,
+        item: &NewClipboardItem,
     ) -> Result<ClipboardItem> {
         let id = Uuid::new_v4().to_string();
         let timestamp = Utc::now().timestamp_millis();
         let created_at = Utc::now();
 
-        // アイテムレコードを保存
+        // 保存中のBLOBが未参照として削除されないよう、保存が終わるまで掃除を待たせる
+        let _guard = self.blob_lock.lock().await;
+        // 保存途中のアイテムが他の接続から見えないよう、1つのトランザクションで保存する
+        let mut tx = self.pool.begin().await?;
+
+        // アイテムレコードを保存（ハッシュは厳密・空白無視の両方）
         sqlx::query(
-            "INSERT INTO clipboard_items (id, primary_format, timestamp, is_favorite, source_app, created_at)
-             VALUES (?, ?, ?, ?, ?, ?)"
+            "INSERT INTO clipboard_items (id, primary_format, timestamp, is_favorite, source_app, source_executable, source_window_title, created_at, content_hash, loose_content_hash, expires_at, is_sensitive, sensitive_kind, selection, kind, kind_confidence, kind_detail)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(&id)
-        .bind(primary_format)
+        .bind(&item.primary_format)
         .bind(timestamp)
-        .bind(false)
-        .bind(source_app)
+        .bind(item.is_favorite)
+        .bind(&item.source.name)
+        .bind(&item.source.executable)
+        .bind(&item.source.window_title)
         .bind(created_at)
-        .execute(&self.pool)
+        .bind(item.content_hash(false))
+        .bind(item.content_hash(true))
+        .bind(item.expires_at)
+        .bind(item.sensitive_kind.is_some())
+        .bind(&item.sensitive_kind)
+        .bind(item.selection.as_str())
+        .bind(item.classification.as_ref().map(|c| c.kind.as_str()))
+        .bind(item.classification.as_ref().map(|c| c.confidence))
+        .bind(item.classification.as_ref().and_then(|c| c.detail.as_deref()))
+        .execute(&mut *tx)
         .await?;
 
+        let blob_threshold = item.blob_threshold.unwrap_or(DEFAULT_BLOB_THRESHOLD);
+
         // 各形式のコンテンツを保存
-        for (format, content) in format_contents {
+        for (format, content) in &item.format_contents {
             let data_size = content.len() as i64;
+
+            // 閾値を超えるテキストはBLOBストアに退避し、先頭部分のみ検索用に残す
+            let offload = content.len() > blob_threshold || item.offloaded_formats.contains(format);
+            let (stored, blob_ref) = if offload {
+                let blob_ref = self.store_blob(&mut *tx, content.as_bytes()).await?;
+                (
+                    truncate_to_char_boundary(content, BLOB_TEXT_INDEX_BYTES),
+                    Some(blob_ref),
+                )
+            } else {
+                (content.as_str(), None)
+            };
+
             sqlx::query(
-                "INSERT INTO clipboard_contents (item_id, format, content, data_size, created_at)
-                 VALUES (?, ?, ?, ?, ?)",
+                "INSERT INTO clipboard_contents (item_id, format, content, data_size, content_hash, blob_ref, blob_kind, is_derived, is_truncated, original_size, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             )
             .bind(&id)
             .bind(format)
-            .bind(content)
+            .bind(stored)
             .bind(data_size)
+            .bind(hash::content_hash(content, false))
+            .bind(&blob_ref)
+            .bind(blob_ref.as_ref().map(|_| "text"))
+            .bind(item.derived_formats.contains(format))
+            .bind(item.truncated_formats.contains_key(format))
+            .bind(item.truncated_formats.get(format).map(|size| *size as i64))
             .bind(created_at)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
         }
 
+        // バイナリ形式のコンテンツを保存（全文検索の対象外、閾値を超える場合はBLOBストアに退避）
+        for (format, bytes) in &item.binary_contents {
+            let offload = bytes.len() > blob_threshold || item.offloaded_formats.contains(format);
+            let (content_blob, blob_ref) = if offload {
+                (None, Some(self.store_blob(&mut *tx, bytes).await?))
+            } else {
+                (Some(bytes), None)
+            };
+
+            sqlx::query(
+                "INSERT INTO clipboard_contents (item_id, format, content, content_blob, data_size, content_hash, blob_ref, blob_kind, created_at)
+                 VALUES (?, ?, '', ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&id)
+            .bind(format)
+            .bind(content_blob)
+            .bind(bytes.len() as i64)
+            .bind(hash::binary_hash(bytes))
+            .bind(&blob_ref)
+            .bind(blob_ref.as_ref().map(|_| "binary"))
+            .bind(created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // 画像のメタデータとサムネイルを保存
+        for (format, image) in &item.images {
+            Self::save_image_metadata(&mut *tx, &id, format, image).await?;
+        }
+
+        // ファイルの情報とタグを保存
+        Self::write_file_entries(&mut tx, &id, &item.files).await?;
+        for tag in &item.tags {
+            sqlx::query("INSERT OR IGNORE INTO clipboard_tags (item_id, tag) VALUES (?, ?)")
+                .bind(&id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
         // 保存したアイテムを取得して返す
         self.get_item_by_id(&id).await
     }
@@ -183,16 +1077,32 @@ impl Database {
         &self,
         limit: Option<u32>,
         offset: Option<u32>,
+    ) -> Result<Vec<ClipboardItem>> {
+        self.get_history_with(limit, offset, None, ContentLoad::Full)
+            .await
+    }
+
+    /// 読み込み方法を指定して履歴を取得（セレクション指定時はその取得元のみ）
+    async fn get_history_with(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        selection: Option<ClipboardSelection>,
+        load: ContentLoad,
     ) -> Result<Vec<ClipboardItem>> {
         let limit = limit.unwrap_or(100);
         let offset = offset.unwrap_or(0);
 
-        let items = sqlx::query(
-            "SELECT id, primary_format, timestamp, is_favorite, source_app, created_at
+        let items = sqlx::query(&format!(
+            "SELECT {ITEM_COLUMNS}
              FROM clipboard_items
+             WHERE (expires_at IS NULL OR expires_at > ?) AND (? IS NULL OR selection = ?)
              ORDER BY timestamp DESC
-             LIMIT ? OFFSET ?",
-        )
+             LIMIT ? OFFSET ?"
+        ))
+        .bind(Utc::now().timestamp_millis())
+        .bind(selection.map(ClipboardSelection::as_str))
+        .bind(selection.map(ClipboardSelection::as_str))
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
@@ -202,85 +1112,195 @@ impl Database {
         for item_row in items {
             let item_id: String = item_row.get("id");
 
-            // コンテンツを取得
-            let contents = sqlx::query(
-                "SELECT item_id, format, content, data_size, created_at
-                 FROM clipboard_contents
-                 WHERE item_id = ?
-                 ORDER BY format",
-            )
-            .bind(&item_id)
-            .fetch_all(&self.pool)
-            .await?
-            .into_iter()
-            .map(|row| ClipboardContent {
-                item_id: row.get("item_id"),
-                format: row.get("format"),
-                content: row.get("content"),
-                data_size: row.get("data_size"),
-                created_at: row.get("created_at"),
-            })
-            .collect();
-
-            result.push(ClipboardItem {
-                id: item_id,
-                primary_format: item_row.get("primary_format"),
-                timestamp: item_row.get("timestamp"),
-                is_favorite: item_row.get("is_favorite"),
-                source_app: item_row.get("source_app"),
-                created_at: item_row.get("created_at"),
-                contents,
-            });
+            // コンテンツとファイルの情報を取得
+            let contents = self.get_contents(&item_id, load).await?;
+            let files = self.get_file_entries(&item_id).await?;
+            let tags = self.get_tags(&item_id).await?;
+            result.push(item_from_row(&item_row, contents, files, tags));
         }
 
         Ok(result)
     }
 
-    /// フロントエンド互換性のためのDisplayClipboardItemを取得
+    /// アイテムに紐づく全形式のコンテンツを取得
+    async fn get_contents(
+        &self,
+        item_id: &str,
+        load: ContentLoad,
+    ) -> Result<Vec<ClipboardContent>> {
+        // 一覧表示用ではサムネイルがあれば元画像の代わりに返し、画像以外のバイナリ形式は読み込まない
+        let blob_column = match load {
+            ContentLoad::Full => "cc.content_blob",
+            ContentLoad::Preview => {
+                "COALESCE(ci.thumbnail, CASE WHEN cc.format LIKE 'image/%' THEN cc.content_blob END)"
+            }
+        };
+
+        let mut contents: Vec<ClipboardContent> = sqlx::query(&format!(
+            "SELECT cc.item_id, cc.format, cc.content, {blob_column} AS content_blob,
+                    cc.blob_ref, cc.blob_kind, cc.data_size, cc.content_hash, cc.is_derived,
+                    cc.is_truncated, cc.original_size, cc.created_at,
+                    ci.width, ci.height, ci.pixel_format, ci.byte_size,
+                    ci.thumbnail_width, ci.thumbnail_height
+             FROM clipboard_contents cc
+             LEFT JOIN clipboard_images ci ON ci.item_id = cc.item_id AND ci.format = cc.format
+             WHERE cc.item_id = ?
+             ORDER BY cc.format"
+        ))
+        .bind(item_id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| ClipboardContent {
+            item_id: row.get("item_id"),
+            format: row.get("format"),
+            content: row.get("content"),
+            content_blob: row.get("content_blob"),
+            blob_ref: row.get("blob_ref"),
+            blob_kind: row.get("blob_kind"),
+            data_size: row.get("data_size"),
+            content_hash: row.get("content_hash"),
+            image_metadata: row
+                .get::<Option<u32>, _>("width")
+                .map(|width| ImageMetadata {
+                    width,
+                    height: row.get("height"),
+                    pixel_format: row.get("pixel_format"),
+                    byte_size: row.get("byte_size"),
+                    thumbnail_width: row.get("thumbnail_width"),
+                    thumbnail_height: row.get("thumbnail_height"),
+                }),
+            is_derived: row.get("is_derived"),
+            is_truncated: row.get("is_truncated"),
+            original_size: row.get("original_size"),
+            is_partial: false,
+            created_at: row.get("created_at"),
+        })
+        .collect();
+
+        for content in &mut contents {
+            self.load_blob_content(content, load).await;
+        }
+
+        Ok(contents)
+    }
+
+    /// アイテムに含まれるファイルの情報を取得（ファイルリスト内の順番）
+    pub async fn get_file_entries(&self, item_id: &str) -> Result<Vec<FileEntry>> {
+        let entries = sqlx::query(
+            "SELECT path, file_exists, is_dir, size, modified_at, mime_type, checked_at
+             FROM clipboard_files
+             WHERE item_id = ?
+             ORDER BY position",
+        )
+        .bind(item_id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| FileEntry {
+            path: row.get("path"),
+            exists: row.get("file_exists"),
+            is_dir: row.get("is_dir"),
+            size: row.get("size"),
+            modified_at: row.get("modified_at"),
+            mime_type: row.get("mime_type"),
+            checked_at: row.get("checked_at"),
+        })
+        .collect();
+
+        Ok(entries)
+    }
+
+    /// アイテムに含まれるファイルの情報を保存（既存の情報は置き換える）
+    pub async fn save_file_entries(&self, item_id: &str, entries: &[FileEntry]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        Self::write_file_entries(&mut tx, item_id, entries).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// トランザクション内でファイルの情報を置き換える
+    async fn write_file_entries(
+        conn: &mut SqliteConnection,
+        item_id: &str,
+        entries: &[FileEntry],
+    ) -> Result<()> {
+        sqlx::query("DELETE FROM clipboard_files WHERE item_id = ?")
+            .bind(item_id)
+            .execute(&mut *conn)
+            .await?;
+
+        for (position, entry) in entries.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO clipboard_files
+                 (item_id, position, path, file_exists, is_dir, size, modified_at, mime_type, checked_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(item_id)
+            .bind(position as i64)
+            .bind(&entry.path)
+            .bind(entry.exists)
+            .bind(entry.is_dir)
+            .bind(entry.size)
+            .bind(entry.modified_at)
+            .bind(&entry.mime_type)
+            .bind(entry.checked_at)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// アイテムに付けられたタグを取得
+    pub async fn get_tags(&self, item_id: &str) -> Result<Vec<String>> {
+        let tags =
+            sqlx::query_scalar("SELECT tag FROM clipboard_tags WHERE item_id = ? ORDER BY tag")
+                .bind(item_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(tags)
+    }
+
+    /// フロントエンド互換性のためのDisplayClipboardItemを取得（画像はサムネイル）
     pub async fn get_display_history(
         &self,
         limit: Option<u32>,
         offset: Option<u32>,
+        selection: Option<ClipboardSelection>,
     ) -> Result<Vec<DisplayClipboardItem>> {
-        let items = self.get_history(limit, offset).await?;
+        let items = self
+            .get_history_with(limit, offset, selection, ContentLoad::Preview)
+            .await?;
 
-        let mut result = Vec::new();
-        for item in items {
-            let available_formats: Vec<String> =
-                item.contents.iter().map(|c| c.format.clone()).collect();
-            let format_contents: std::collections::HashMap<String, String> = item
-                .contents
-                .iter()
-                .map(|c| (c.format.clone(), c.content.clone()))
-                .collect();
-
-            // プライマリコンテンツを取得
-            let primary_content = format_contents
-                .get(&item.primary_format)
-                .cloned()
-                .unwrap_or_else(|| "[No content]".to_string());
-
-            result.push(DisplayClipboardItem {
-                id: item.id,
-                content: primary_content,
-                content_type: item.primary_format.clone(),
-                timestamp: item.timestamp,
-                is_favorite: item.is_favorite,
-                source_app: item.source_app,
-                created_at: item.created_at,
-                available_formats: Some(available_formats),
-                format_contents: Some(format_contents),
-            });
-        }
+        Ok(items.into_iter().map(DisplayClipboardItem::from).collect())
+    }
 
-        Ok(result)
+    /// エクスポート用に履歴を取得（元データを含み、機密データは除外）
+    pub async fn get_export_history(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Vec<DisplayClipboardItem>> {
+        let items = self
+            .get_history_with(limit, offset, None, ContentLoad::Full)
+            .await?;
+
+        Ok(items
+            .into_iter()
+            .filter(|item| !item.is_sensitive)
+            .map(DisplayClipboardItem::from)
+            .collect())
     }
 
-    /// 全文検索で履歴を検索（正規化されたデータベース用）
+    /// 全文検索で履歴を検索（正規化されたデータベース用、画像はサムネイル）
     pub async fn search_history(
         &self,
         query: &str,
         limit: Option<u32>,
+        selection: Option<ClipboardSelection>,
     ) -> Result<Vec<ClipboardItem>> {
         let limit = limit.unwrap_or(50);
 
@@ -288,18 +1308,22 @@ impl Database {
             "SELECT DISTINCT cs.item_id
              FROM clipboard_search cs
              JOIN clipboard_items ci ON cs.item_id = ci.id
-             WHERE clipboard_search MATCH ?
+             WHERE clipboard_search MATCH ? AND (ci.expires_at IS NULL OR ci.expires_at > ?)
+               AND (? IS NULL OR ci.selection = ?)
              ORDER BY ci.timestamp DESC
              LIMIT ?",
         )
         .bind(query)
+        .bind(Utc::now().timestamp_millis())
+        .bind(selection.map(ClipboardSelection::as_str))
+        .bind(selection.map(ClipboardSelection::as_str))
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
         let mut result = Vec::new();
         for item_id in item_ids {
-            if let Ok(item) = self.get_item_by_id(&item_id).await {
+            if let Ok(item) = self.get_item_with(&item_id, ContentLoad::Preview).await {
                 result.push(item);
             }
         }
@@ -309,60 +1333,103 @@ impl Database {
 
     /// IDでアイテムを取得（正規化されたデータベース用）
     pub async fn get_item_by_id(&self, id: &str) -> Result<ClipboardItem> {
-        let item_row = sqlx::query(
-            "SELECT id, primary_format, timestamp, is_favorite, source_app, created_at
+        self.get_item_with(id, ContentLoad::Full).await
+    }
+
+    /// 読み込み方法を指定してアイテムを取得
+    async fn get_item_with(&self, id: &str, load: ContentLoad) -> Result<ClipboardItem> {
+        let item_row = sqlx::query(&format!(
+            "SELECT {ITEM_COLUMNS}
              FROM clipboard_items
-             WHERE id = ?",
-        )
+             WHERE id = ?"
+        ))
         .bind(id)
         .fetch_one(&self.pool)
         .await?;
 
-        let contents = sqlx::query(
-            "SELECT item_id, format, content, data_size, created_at
-             FROM clipboard_contents
-             WHERE item_id = ?
-             ORDER BY format",
+        let contents = self.get_contents(id, load).await?;
+        let files = self.get_file_entries(id).await?;
+        let tags = self.get_tags(id).await?;
+
+        Ok(item_from_row(&item_row, contents, files, tags))
+    }
+
+    /// テキストコンテンツのハッシュで既存アイテムを検索（最新の1件）
+    pub async fn find_item_id_by_content(
+        &self,
+        content: &str,
+        ignore_whitespace: bool,
+    ) -> Result<Option<String>> {
+        self.find_item_id_by_hash(
+            &hash::content_hash(content, ignore_whitespace),
+            ignore_whitespace,
+            ClipboardSelection::Clipboard,
         )
+        .await
+    }
+
+    /// 同じセレクションからの取得でコンテンツハッシュが一致する既存アイテムを検索（最新の1件）
+    pub async fn find_item_id_by_hash(
+        &self,
+        content_hash: &str,
+        ignore_whitespace: bool,
+        selection: ClipboardSelection,
+    ) -> Result<Option<String>> {
+        let query = if ignore_whitespace {
+            "SELECT id FROM clipboard_items WHERE loose_content_hash = ? AND selection = ? ORDER BY timestamp DESC LIMIT 1"
+        } else {
+            "SELECT id FROM clipboard_items WHERE content_hash = ? AND selection = ? ORDER BY timestamp DESC LIMIT 1"
+        };
+
+        let id: Option<String> = sqlx::query_scalar(query)
+            .bind(content_hash)
+            .bind(selection.as_str())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// 既存アイテムを先頭に移動し、コピー回数を加算
+    pub async fn move_item_to_top(&self, id: &str) -> Result<ClipboardItem> {
+        sqlx::query(
+            "UPDATE clipboard_items SET timestamp = ?, copy_count = copy_count + 1 WHERE id = ?",
+        )
+        .bind(Utc::now().timestamp_millis())
         .bind(id)
-        .fetch_all(&self.pool)
-        .await?
-        .into_iter()
-        .map(|row| ClipboardContent {
-            item_id: row.get("item_id"),
-            format: row.get("format"),
-            content: row.get("content"),
-            data_size: row.get("data_size"),
-            created_at: row.get("created_at"),
-        })
-        .collect();
+        .execute(&self.pool)
+        .await?;
 
-        Ok(ClipboardItem {
-            id: item_row.get("id"),
-            primary_format: item_row.get("primary_format"),
-            timestamp: item_row.get("timestamp"),
-            is_favorite: item_row.get("is_favorite"),
-            source_app: item_row.get("source_app"),
-            created_at: item_row.get("created_at"),
-            contents,
-        })
+        self.get_item_by_id(id).await
     }
 
-    /// お気に入りの切り替え
-    pub async fn toggle_favorite(&self, id: &str) -> Result<bool> {
-        let current: bool =
-            sqlx::query_scalar("SELECT is_favorite FROM clipboard_items WHERE id = ?")
-                .bind(id)
-                .fetch_one(&self.pool)
-                .await?;
+    /// ClipOneから書き戻して使用したことを記録（move_to_topなら先頭に移動）
+    pub async fn record_item_use(&self, id: &str, move_to_top: bool) -> Result<ClipboardItem> {
+        let now = Utc::now().timestamp_millis();
+        sqlx::query(
+            "UPDATE clipboard_items
+             SET last_used_at = ?, use_count = use_count + 1,
+                 timestamp = CASE WHEN ? THEN ? ELSE timestamp END
+             WHERE id = ?",
+        )
+        .bind(now)
+        .bind(move_to_top)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
 
-        let new_favorite = !current;
+        self.get_item_by_id(id).await
+    }
 
-        sqlx::query("UPDATE clipboard_items SET is_favorite = ? WHERE id = ?")
-            .bind(new_favorite)
-            .bind(id)
-            .execute(&self.pool)
-            .await?;
+    /// お気に入りの切り替え（同時に切り替えられても更新が失われないよう1つの文で反転する）
+    pub async fn toggle_favorite(&self, id: &str) -> Result<bool> {
+        let new_favorite: bool = sqlx::query_scalar(
+            "UPDATE clipboard_items SET is_favorite = NOT is_favorite WHERE id = ? RETURNING is_favorite",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
 
         Ok(new_favorite)
     }
@@ -375,9 +1442,27 @@ impl Database {
             .execute(&self.pool)
             .await?;
 
+        self.collect_garbage_blobs().await?;
+
         Ok(())
     }
 
+    /// 有効期限を過ぎたアイテムを削除し、削除したIDを返す
+    pub async fn delete_expired_items(&self) -> Result<Vec<String>> {
+        let ids: Vec<String> = sqlx::query_scalar(
+            "DELETE FROM clipboard_items WHERE expires_at IS NOT NULL AND expires_at <= ? RETURNING id",
+        )
+        .bind(Utc::now().timestamp_millis())
+        .fetch_all(&self.pool)
+        .await?;
+
+        if !ids.is_empty() {
+            self.collect_garbage_blobs().await?;
+        }
+
+        Ok(ids)
+    }
+
     /// 履歴をクリア（正規化されたデータベース用）
     pub async fn clear_history(&self) -> Result<()> {
         // 外部キー制約でclipboard_contentsは自動削除される
@@ -385,6 +1470,8 @@ impl Database {
             .execute(&self.pool)
             .await?;
 
+        self.collect_garbage_blobs().await?;
+
         Ok(())
     }
 
@@ -397,27 +1484,41 @@ impl Database {
         Ok(count)
     }
 
-    /// 古いアイテムをクリーンアップ（お気に入り以外）
-    pub async fn cleanup_old_items(&self, max_items: usize) -> Result<()> {
-        sqlx::query(
+    /// 古いアイテムをクリーンアップ（お気に入り以外）し、削除したIDを返す
+    pub async fn cleanup_old_items(&self, max_items: usize) -> Result<Vec<String>> {
+        let ids: Vec<String> = sqlx::query_scalar(
             "DELETE FROM clipboard_items 
              WHERE id NOT IN (
                  SELECT id FROM clipboard_items 
                  WHERE is_favorite = true
                  UNION ALL
-                 SELECT id FROM clipboard_items 
-                 ORDER BY timestamp DESC 
-                 LIMIT ?
-             )",
+                 SELECT id FROM (
+                     SELECT id FROM clipboard_items 
+                     ORDER BY timestamp DESC 
+                     LIMIT ?
+                 )
+             )
+             RETURNING id",
         )
         .bind(max_items as i64)
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        // データベースを最適化
-        sqlx::query("VACUUM").execute(&self.pool).await?;
+        self.collect_garbage_blobs().await?;
 
-        Ok(())
+        // 削除で空いたページを少しずつ解放（VACUUMは書き込みを長時間止めるため使わない）
+        sqlx::query(&format!(
+            "PRAGMA incremental_vacuum({})",
+            INCREMENTAL_VACUUM_PAGES
+        ))
+        .execute(&self.pool)
+        .await?;
+        // WALファイルの内容を本体に反映し、WALファイルを切り詰める
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(ids)
     }
 
     /// 後方互換性のための旧形式インターフェース（単一コンテンツ）
@@ -428,68 +1529,14 @@ impl Database {
         source_app: Option<&str>,
     ) -> Result<DisplayClipboardItem> {
         // 単一形式のコンテンツを正規化されたメソッドで保存
-        let mut format_contents = std::collections::HashMap::new();
-        format_contents.insert(content_type.to_string(), content.to_string());
-
-        let item = self
-            .save_clipboard_item_normalized(content_type, source_app, &format_contents)
-            .await?;
+        let new_item = NewClipboardItem::from_single_content(content, content_type, source_app);
+        let item = self.save_clipboard_item_normalized(&new_item).await?;
 
         // DisplayClipboardItemに変換して返す
-        let available_formats: Vec<String> =
-            item.contents.iter().map(|c| c.format.clone()).collect();
-        let format_contents_map: std::collections::HashMap<String, String> = item
-            .contents
-            .iter()
-            .map(|c| (c.format.clone(), c.content.clone()))
-            .collect();
-
         Ok(DisplayClipboardItem {
-            id: item.id,
             content: content.to_string(),
             content_type: content_type.to_string(),
-            timestamp: item.timestamp,
-            is_favorite: item.is_favorite,
-            source_app: item.source_app,
-            created_at: item.created_at,
-            available_formats: Some(available_formats),
-            format_contents: Some(format_contents_map),
-        })
-    }
-
-    /// 後方互換性のための旧形式インターフェース（複数コンテンツ）
-    pub async fn save_clipboard_item_with_all_formats(
-        &self,
-        content: &str,
-        content_type: &str,
-        source_app: Option<&str>,
-        _available_formats: &[String],
-        primary_format: &str,
-        format_contents: &std::collections::HashMap<String, String>,
-    ) -> Result<DisplayClipboardItem> {
-        let item = self
-            .save_clipboard_item_normalized(primary_format, source_app, format_contents)
-            .await?;
-
-        // DisplayClipboardItemに変換して返す
-        let available_formats_vec: Vec<String> =
-            item.contents.iter().map(|c| c.format.clone()).collect();
-        let format_contents_map: std::collections::HashMap<String, String> = item
-            .contents
-            .iter()
-            .map(|c| (c.format.clone(), c.content.clone()))
-            .collect();
-
-        Ok(DisplayClipboardItem {
-            id: item.id,
-            content: content.to_string(),
-            content_type: content_type.to_string(),
-            timestamp: item.timestamp,
-            is_favorite: item.is_favorite,
-            source_app: item.source_app,
-            created_at: item.created_at,
-            available_formats: Some(available_formats_vec),
-            format_contents: Some(format_contents_map),
+            ..DisplayClipboardItem::from(item)
         })
     }
 }
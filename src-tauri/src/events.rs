@@ -0,0 +1,101 @@
+use crate::commands::clipboard::MonitoringStatus;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, error};
+
+/// アイテムが削除された理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteReason {
+    /// ユーザー操作による削除
+    User,
+    /// 有効期限（機密データ・キャプチャルールのTTLなど）による自動削除
+    Expired,
+    /// 履歴の上限を超えた古いアイテムの整理
+    Cleanup,
+}
+
+/// 履歴・監視状態・設定の変更を通知するイベント
+///
+/// 変更の種類ごとに同名のイベントとして送信する（例: `item-added`）。
+/// ペイロードにはアイテムの内容を含めず、必要な場合は受信側がIDで取得する。
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum AppEvent {
+    /// アイテムが追加された（キャプチャ・インポートなど）
+    ItemAdded { ids: Vec<String> },
+    /// アイテムが更新された（お気に入り・先頭への移動・ファイルの再確認など）
+    ItemUpdated { ids: Vec<String> },
+    /// アイテムが削除された
+    ItemDeleted {
+        ids: Vec<String>,
+        reason: DeleteReason,
+    },
+    /// 履歴がすべて削除された
+    HistoryCleared,
+    /// 監視の開始・停止・一時停止・再開
+    MonitoringStateChanged(MonitoringStatus),
+    /// 設定が変更された（keyがnullの場合は設定全体）
+    SettingsChanged { key: Option<String> },
+}
+
+impl AppEvent {
+    /// イベント名
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEvent::ItemAdded { .. } => "item-added",
+            AppEvent::ItemUpdated { .. } => "item-updated",
+            AppEvent::ItemDeleted { .. } => "item-deleted",
+            AppEvent::HistoryCleared => "history-cleared",
+            AppEvent::MonitoringStateChanged(_) => "monitoring-state-changed",
+            AppEvent::SettingsChanged { .. } => "settings-changed",
+        }
+    }
+}
+
+/// すべてのウィンドウにイベントを送信
+pub fn emit(app: &AppHandle, event: AppEvent) {
+    let name = event.name();
+    if let Err(e) = app.emit(name, &event) {
+        error!(event = name, "イベント送信エラー: {}", e);
+    } else {
+        debug!(event = name, "イベント送信");
+    }
+}
+
+/// 1件のアイテムの追加を通知
+pub fn item_added(app: &AppHandle, id: &str) {
+    emit(
+        app,
+        AppEvent::ItemAdded {
+            ids: vec![id.to_string()],
+        },
+    );
+}
+
+/// 1件のアイテムの更新を通知
+pub fn item_updated(app: &AppHandle, id: &str) {
+    emit(
+        app,
+        AppEvent::ItemUpdated {
+            ids: vec![id.to_string()],
+        },
+    );
+}
+
+/// アイテムの削除を通知（削除がなければ送信しない）
+pub fn items_deleted(app: &AppHandle, ids: Vec<String>, reason: DeleteReason) {
+    if !ids.is_empty() {
+        emit(app, AppEvent::ItemDeleted { ids, reason });
+    }
+}
+
+/// 設定の変更を通知
+pub fn settings_changed(app: &AppHandle, key: Option<&str>) {
+    emit(
+        app,
+        AppEvent::SettingsChanged {
+            key: key.map(str::to_string),
+        },
+    );
+}
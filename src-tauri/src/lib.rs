@@ -5,24 +5,37 @@ use std::sync::{
 };
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Emitter, Manager, WindowEvent,
+    Emitter, Listener, Manager, WindowEvent,
 };
-use tokio::sync::Mutex;
+use tracing::{debug, error, info};
 
 // ウィンドウの表示状態を管理
 static WINDOW_SHOULD_BE_VISIBLE: AtomicBool = AtomicBool::new(false);
 
+mod capture;
 mod commands;
 mod database;
+mod events;
+mod logging;
 
+use capture::pause::{self, PauseDuration};
+use commands::settings::AppSettings;
 use commands::*;
 use database::Database;
 
 /// システムトレイの設定
 fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    // トレイメニューを作成
+    // トレイメニューを作成（先頭は監視状態の表示のみ）
+    let status = MenuItem::with_id(
+        app,
+        "monitoring_status",
+        tray_status_text(&monitoring_status()),
+        false,
+        None::<&str>,
+    )?;
+    let separator_status = PredefinedMenuItem::separator(app)?;
     let show_hide = MenuItem::with_id(
         app,
         "toggle_window",
@@ -31,6 +44,17 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
         None::<&str>,
     )?;
     let separator1 = PredefinedMenuItem::separator(app)?;
+    let pause_5m = MenuItem::with_id(app, "pause_5m", "5分間", true, None::<&str>)?;
+    let pause_1h = MenuItem::with_id(app, "pause_1h", "1時間", true, None::<&str>)?;
+    let pause_restart = MenuItem::with_id(app, "pause_restart", "再起動まで", true, None::<&str>)?;
+    let pause_menu = Submenu::with_items(
+        app,
+        "一時停止…",
+        true,
+        &[&pause_5m, &pause_1h, &pause_restart],
+    )?;
+    let resume = MenuItem::with_id(app, "resume_capture", "記録を再開", true, None::<&str>)?;
+    let separator_pause = PredefinedMenuItem::separator(app)?;
     let settings = MenuItem::with_id(app, "settings", "設定", true, None::<&str>)?;
     let about = MenuItem::with_id(app, "about", "ClipOne について", true, None::<&str>)?;
     let separator2 = PredefinedMenuItem::separator(app)?;
@@ -39,8 +63,13 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
     let menu = Menu::with_items(
         app,
         &[
+            &status,
+            &separator_status,
             &show_hide,
             &separator1,
+            &pause_menu,
+            &resume,
+            &separator_pause,
             &settings,
             &about,
             &separator2,
@@ -55,11 +84,11 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
     let (width, height) = img.dimensions();
     let icon = Image::new_owned(rgba.into_raw(), width, height);
 
-    let _tray = TrayIconBuilder::with_id("main-tray")
+    let tray = TrayIconBuilder::with_id("main-tray")
         .show_menu_on_left_click(false) // 左クリックでメニューを表示しない
         .menu(&menu)
         .icon(icon)
-        .tooltip("ClipOne - クリップボード履歴管理\n左クリック: 表示切り替え\n右クリック: メニュー")
+        .tooltip(tray_tooltip(&monitoring_status()))
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
                 button: MouseButton::Left,
@@ -102,6 +131,18 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
                         }
                     }
                 }
+                "pause_5m" => {
+                    pause::pause(app, PauseDuration::FiveMinutes);
+                }
+                "pause_1h" => {
+                    pause::pause(app, PauseDuration::OneHour);
+                }
+                "pause_restart" => {
+                    pause::pause(app, PauseDuration::UntilRestart);
+                }
+                "resume_capture" => {
+                    pause::resume(app);
+                }
                 "settings" => {
                     // 設定画面を表示
                     if let Some(window) = app.get_webview_window("main") {
@@ -125,7 +166,7 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
                     }
                 }
                 "quit" => {
-                    println!("✋ アプリケーションを終了します");
+                    info!("アプリケーションを終了します");
                     app.exit(0);
                 }
                 _ => {}
@@ -133,10 +174,38 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
         })
         .build(app)?;
 
-    println!("✅ システムトレイが初期化されました");
+    // 監視状態が変わるたびにトレイの表示を更新
+    app.listen_any("monitoring-state-changed", move |_| {
+        let current = monitoring_status();
+        if let Err(e) = status.set_text(tray_status_text(&current)) {
+            error!("トレイメニュー更新エラー: {}", e);
+        }
+        if let Err(e) = tray.set_tooltip(Some(tray_tooltip(&current))) {
+            error!("トレイツールチップ更新エラー: {}", e);
+        }
+    });
+
+    info!("システムトレイが初期化されました");
     Ok(())
 }
 
+/// トレイメニューに表示する監視状態
+fn tray_status_text(status: &MonitoringStatus) -> String {
+    format!("状態: {}", status.summary())
+}
+
+/// トレイアイコンのツールチップ（監視状態と直近のエラーを含む）
+fn tray_tooltip(status: &MonitoringStatus) -> String {
+    let mut tooltip = format!(
+        "ClipOne - クリップボード履歴管理\n{}\n左クリック: 表示切り替え\n右クリック: メニュー",
+        tray_status_text(status)
+    );
+    if let Some(error) = &status.health.last_error {
+        tooltip.push_str(&format!("\n直近のエラー: {}", error));
+    }
+    tooltip
+}
+
 /// ウィンドウイベントの設定
 fn setup_window_events(app: &tauri::App) {
     if let Some(window) = app.get_webview_window("main") {
@@ -147,26 +216,26 @@ fn setup_window_events(app: &tauri::App) {
                 // ただし、トレイから意図的に表示された直後の場合は隠さない
                 let should_be_visible = WINDOW_SHOULD_BE_VISIBLE.load(Ordering::Relaxed);
 
-                println!(
-                    "🚪 CloseRequested イベント受信, should_be_visible = {}",
+                debug!(
+                    "CloseRequested イベント受信, should_be_visible = {}",
                     should_be_visible
                 );
 
                 if should_be_visible {
                     // 意図的に表示された状態なので、クローズ処理をスキップ
-                    println!("🔼 ウィンドウが意図的に表示されているため、クローズ処理をスキップ");
+                    debug!("ウィンドウが意図的に表示されているため、クローズ処理をスキップ");
                     // 少し待ってからフラグをリセット（初期化後の自動クローズを防ぐ）
                     std::thread::spawn(|| {
                         std::thread::sleep(std::time::Duration::from_millis(1000));
                         WINDOW_SHOULD_BE_VISIBLE.store(false, Ordering::Relaxed);
-                        println!("⏰ WINDOW_SHOULD_BE_VISIBLE フラグをリセット");
+                        debug!("WINDOW_SHOULD_BE_VISIBLE フラグをリセット");
                     });
                 } else {
                     // 通常のクローズ処理：トレイに隠す
                     api.prevent_close();
                     if let Some(window) = app_handle.get_webview_window("main") {
                         let _ = window.hide();
-                        println!("🔽 ウィンドウをトレイに最小化しました");
+                        debug!("ウィンドウをトレイに最小化しました");
                     }
                 }
             }
@@ -175,6 +244,8 @@ fn setup_window_events(app: &tauri::App) {
 }
 
 pub fn run() {
+    let settings = tauri::async_runtime::block_on(AppSettings::load()).unwrap_or_default();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
@@ -182,7 +253,17 @@ pub fn run() {
         ))
         .plugin(tauri_plugin_window_state::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
+        .setup(move |app| {
+            // ログ出力を初期化（自動起動などで作業ディレクトリが異なってもアプリのログディレクトリに出力）
+            let log_dir = app
+                .path()
+                .app_log_dir()
+                .inspect_err(|e| eprintln!("ログディレクトリ取得エラー: {}", e))
+                .ok();
+            if let Err(e) = logging::init(settings.log_level, log_dir) {
+                eprintln!("ログ初期化エラー: {}", e);
+            }
+
             // データベース接続を初期化（同期的に実行）
             let runtime = tokio::runtime::Runtime::new().unwrap();
             let database = runtime.block_on(async { Database::new().await });
@@ -190,10 +271,27 @@ pub fn run() {
             match database {
                 Ok(db) => {
                     // データベースを状態管理に追加
-                    let db_state = Arc::new(Mutex::new(db));
-                    app.manage(db_state);
-                    println!("データベース接続が正常に初期化されました");
-                    println!("クリップボード監視はフロントエンドで開始されます");
+                    let db_state = Arc::new(db);
+                    app.manage(Arc::clone(&db_state));
+                    info!("データベース接続が正常に初期化されました");
+
+                    // 機密データなどの有効期限切れアイテムは監視の状態に関係なく削除する
+                    capture::start_expiry_sweeper(app.handle().clone(), Arc::clone(&db_state));
+
+                    // 前回の一時停止を引き継ぎ、設定に応じて監視を開始（フロントエンドは状態を表示するのみ）
+                    let handle = app.handle().clone();
+                    let start_on_launch =
+                        settings.monitor_on_startup && !capture::lifecycle::load().stopped;
+                    tauri::async_runtime::spawn(async move {
+                        pause::restore(&handle);
+                        if !start_on_launch {
+                            info!("前回停止したまま終了したため、クリップボード監視を開始しません");
+                            return;
+                        }
+                        if let Err(e) = start_monitoring(handle, db_state).await {
+                            error!("クリップボード監視開始エラー: {}", e);
+                        }
+                    });
 
                     // トレイアイコンとメニューの設定
                     setup_system_tray(app)?;
@@ -204,7 +302,7 @@ pub fn run() {
                     Ok(())
                 }
                 Err(e) => {
-                    eprintln!("データベース初期化エラー: {}", e);
+                    error!("データベース初期化エラー: {}", e);
                     Err(e.into())
                 }
             }
@@ -219,17 +317,22 @@ pub fn run() {
             start_clipboard_monitoring,
             stop_clipboard_monitoring,
             get_monitoring_status,
+            pause_clipboard_monitoring,
+            resume_clipboard_monitoring,
             test_clipboard_rs,
             // クリップボード基本操作
             get_clipboard_text,
             set_clipboard_text,
+            restore_clipboard_item,
             has_clipboard_text,
+            get_clipboard_formats,
             clear_clipboard_text,
             // 履歴管理
             get_clipboard_history,
             search_clipboard_history,
             get_clipboard_item,
             toggle_favorite,
+            revalidate_item_files,
             delete_clipboard_item,
             clear_clipboard_history,
             get_clipboard_stats,
@@ -239,6 +342,20 @@ pub fn run() {
             save_app_settings,
             update_setting,
             reset_settings,
+            // ログ
+            get_recent_logs,
+            // アプリ別キャプチャルール
+            get_app_capture_rules,
+            save_app_capture_rule,
+            delete_app_capture_rule,
+            reorder_app_capture_rules,
+            set_app_capture_default_action,
+            // キャプチャルール
+            get_capture_rules,
+            save_capture_rule,
+            delete_capture_rule,
+            reorder_capture_rules,
+            test_capture_rule,
             // アプリ情報
             get_app_info,
             // エクスポート/インポート
@@ -0,0 +1,238 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{self, Rotation};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+/// ログファイル名の接頭辞・拡張子（日付ごとに clipone.YYYY-MM-DD.log）
+const LOG_FILE_PREFIX: &str = "clipone";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// 残すログファイルの数（日数）
+const MAX_LOG_FILES: usize = 7;
+
+/// get_recent_logsで返す行数の上限
+const MAX_RECENT_LINES: usize = 5000;
+
+/// ログファイルの末尾から1回に読み込むサイズ（バイト）
+const TAIL_CHUNK_BYTES: u64 = 64 * 1024;
+
+/// ログの出力レベル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    /// 取得したクリップボードの内容ごとのサイズ・ハッシュも出力
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+// 実行中にレベルを変更するためのハンドル
+static FILTER: OnceLock<reload::Handle<Targets, Registry>> = OnceLock::new();
+
+// ファイルへの書き込みスレッドを終了まで保持する
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+// ログファイルの出力先（ファイルへの出力を開始できた場合のみ）
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// レベルごとの出力対象（依存クレートは警告以上のみ）
+fn targets(level: LogLevel) -> Targets {
+    Targets::new()
+        .with_default(level.filter())
+        .with_target("sqlx", LevelFilter::WARN)
+        .with_target("tao", LevelFilter::WARN)
+        .with_target("wry", LevelFilter::WARN)
+}
+
+/// 標準出力と日付ごとにローテーションするログファイル（アプリのログディレクトリ）への出力を初期化
+///
+/// ログファイルを作成できない場合は標準エラー出力に理由を出し、標準出力のみに出力する。
+pub fn init(level: LogLevel, dir: Option<PathBuf>) -> Result<()> {
+    let file_layer = match dir.map(|dir| open_appender(&dir).map(|appender| (dir, appender))) {
+        Some(Ok((dir, appender))) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let _ = GUARD.set(guard);
+            let _ = LOG_DIR.set(dir);
+            Some(fmt::layer().with_writer(writer).with_ansi(false))
+        }
+        Some(Err(e)) => {
+            eprintln!(
+                "ログファイルを作成できないため標準出力のみに出力します: {}",
+                e
+            );
+            None
+        }
+        None => {
+            eprintln!("ログディレクトリが不明なため標準出力のみに出力します");
+            None
+        }
+    };
+
+    let (filter, handle) = reload::Layer::new(targets(level));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .try_init()?;
+
+    let _ = FILTER.set(handle);
+    if let Some(dir) = LOG_DIR.get() {
+        tracing::info!("ログ出力先: {}", dir.display());
+    }
+    Ok(())
+}
+
+/// ディレクトリを作成し、日付ごとにローテーションするログファイルを開く
+fn open_appender(dir: &Path) -> Result<rolling::RollingFileAppender> {
+    let open = || -> Result<rolling::RollingFileAppender> {
+        std::fs::create_dir_all(dir)?;
+        Ok(rolling::Builder::new()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)?)
+    };
+    open().map_err(|e| anyhow::anyhow!("{} ({})", e, dir.display()))
+}
+
+/// 出力レベルを変更（設定の変更時）
+pub fn set_level(level: LogLevel) {
+    if let Some(handle) = FILTER.get() {
+        if let Err(e) = handle.reload(targets(level)) {
+            tracing::error!(error = %e, "ログレベル変更エラー");
+        }
+    }
+}
+
+/// 新しいログファイルから順に末尾を読み、直近のlimit行を古い順に返す
+pub fn recent_lines(limit: usize) -> Result<Vec<String>> {
+    let limit = limit.min(MAX_RECENT_LINES);
+    let Some(dir) = LOG_DIR.get() else {
+        return Ok(Vec::new());
+    };
+    let mut files: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+            })
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    // ファイル名の日付順が作成順になる
+    files.sort();
+
+    let mut lines = Vec::new();
+    for path in files.iter().rev() {
+        lines.extend(tail_lines(path, limit - lines.len())?);
+        if lines.len() >= limit {
+            break;
+        }
+    }
+
+    lines.reverse();
+    Ok(lines)
+}
+
+/// ファイルの末尾から最大limit行を新しい順に返す（ファイル全体は読み込まない）
+fn tail_lines(path: &Path, limit: usize) -> Result<Vec<String>> {
+    let mut file = File::open(path)?;
+    let mut start = file.metadata()?.len();
+    let mut buffer = Vec::new();
+    let mut newlines = 0;
+
+    // 先頭の行が欠けていても捨てられるよう、limit行より1行多く読むまで後ろから読み足す
+    while start > 0 && newlines <= limit {
+        let chunk_start = start.saturating_sub(TAIL_CHUNK_BYTES);
+        let mut chunk = vec![0; (start - chunk_start) as usize];
+        file.seek(SeekFrom::Start(chunk_start))?;
+        file.read_exact(&mut chunk)?;
+        newlines += chunk.iter().filter(|&&byte| byte == b'\n').count();
+        chunk.extend_from_slice(&buffer);
+        buffer = chunk;
+        start = chunk_start;
+    }
+
+    let text = String::from_utf8_lossy(&buffer);
+    let mut lines: Vec<&str> = text.lines().collect();
+    // ファイルの途中から読んだ場合、先頭の行は途中から始まっている
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+
+    Ok(lines
+        .into_iter()
+        .rev()
+        .take(limit)
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_log(lines: usize) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("clipone-test-{}.log", uuid::Uuid::new_v4()));
+        let content: String = (0..lines).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn tail_returns_newest_lines_first() {
+        let path = write_log(10);
+        let lines = tail_lines(&path, 3).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(lines, vec!["line 9", "line 8", "line 7"]);
+    }
+
+    #[test]
+    fn tail_returns_whole_file_when_shorter_than_limit() {
+        let path = write_log(2);
+        let lines = tail_lines(&path, 5).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(lines, vec!["line 1", "line 0"]);
+    }
+
+    #[test]
+    fn tail_reads_across_chunks_without_partial_lines() {
+        // 複数のチャンクにまたがる大きさ
+        let path = write_log(50_000);
+        let lines = tail_lines(&path, 20_000).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(lines.len(), 20_000);
+        assert_eq!(lines[0], "line 49999");
+        assert_eq!(lines[19_999], "line 30000");
+    }
+}
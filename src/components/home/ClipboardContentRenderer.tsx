@@ -1,23 +1,54 @@
 import { openUrl } from "@tauri-apps/plugin-opener";
+import { useEffect, useState } from "react";
 import { useClipboardControl } from "@/hooks/useClipboardControl";
 import { useImageWindow } from "@/hooks/useImageWindow";
-import { parseFileList, truncateText } from "@/utils/textUtils";
+import type { ContentKind } from "@/types/clipboard";
+import type { FileEntry, ImageMetadata } from "@/types/clipboardActions";
+import { historyApi } from "@/utils/tauri-api";
+import { formatFileSize, getFileIcon, parseFileList, truncateText } from "@/utils/textUtils";
 
 interface ClipboardContentRendererProps {
+  itemId?: string;
   format: string;
+  kind?: ContentKind; // メイン形式の場合の内容の種類
   content: string;
   isExpanded: boolean;
+  imageMetadata?: ImageMetadata;
+  files?: FileEntry[]; // 取得時に解決したファイルの情報
 }
 
-export function ClipboardContentRenderer({ format, content, isExpanded }: ClipboardContentRendererProps) {
+export function ClipboardContentRenderer({
+  itemId,
+  format,
+  kind,
+  content,
+  isExpanded,
+  imageMetadata,
+  files,
+}: ClipboardContentRendererProps) {
   const { showImageWindow } = useImageWindow();
   const { notifyStartCopy } = useClipboardControl();
+  const [fileEntries, setFileEntries] = useState(files ?? []);
+
+  useEffect(() => {
+    setFileEntries(files ?? []);
+  }, [files]);
+
+  // ファイルが削除・移動されていないか再確認
+  const revalidateFiles = async () => {
+    if (!itemId) return;
+    try {
+      setFileEntries(await historyApi.revalidateFiles(itemId));
+    } catch (error) {
+      console.error("ファイル再確認エラー:", error);
+    }
+  };
 
   const shouldTruncate = content.length > 100;
   const displayContent = isExpanded || !shouldTruncate ? content : truncateText(content);
 
   // URL表示 - クリック可能なリンク
-  if (format === "text/uri-list") {
+  if (format === "text/uri-list" || kind === "url") {
     return (
       <div className="text-sm">
         <div
@@ -48,6 +79,59 @@ export function ClipboardContentRenderer({ format, content, isExpanded }: Clipbo
     );
   }
 
+  // 解決済みのファイル情報がある場合 - 存在しないファイルを区別して表示
+  if (fileEntries.length > 0 && (format === "application/x-file-list" || format === "text/plain")) {
+    const displayEntries = isExpanded ? fileEntries : fileEntries.slice(0, 3);
+    const missingCount = fileEntries.filter((entry) => !entry.exists).length;
+
+    return (
+      <div className="text-sm space-y-1">
+        {displayEntries.map((entry, i) => {
+          const filename = entry.path.split(/[\\/]/).pop() || entry.path;
+          return (
+            <div
+              key={`${entry.path}-${i}`}
+              className={`flex items-center gap-2 ${entry.exists ? "" : "text-muted-foreground"}`}
+              title={entry.path}
+            >
+              <span className="text-base">{entry.is_dir ? "📁" : getFileIcon(filename)}</span>
+              <span className={`break-words ${entry.exists ? "" : "line-through"}`}>{filename}</span>
+              <span className="text-xs text-muted-foreground">
+                {entry.exists ? (entry.size !== null ? formatFileSize(entry.size) : "") : "見つかりません"}
+              </span>
+            </div>
+          );
+        })}
+        {!isExpanded && fileEntries.length > 3 && (
+          <div className="text-xs text-muted-foreground">+{fileEntries.length - 3}個のファイル...</div>
+        )}
+        {isExpanded && (
+          <div className="flex items-center gap-2 text-xs text-muted-foreground">
+            {missingCount > 0 && <span>{missingCount}個のファイルが見つかりません</span>}
+            <span
+              className="underline cursor-pointer hover:text-foreground"
+              onClick={(e) => {
+                e.stopPropagation();
+                revalidateFiles();
+              }}
+              onKeyDown={(e) => {
+                if (e.key === "Enter" || e.key === " ") {
+                  e.preventDefault();
+                  e.stopPropagation();
+                  revalidateFiles();
+                }
+              }}
+              role="button"
+              tabIndex={0}
+            >
+              再確認
+            </span>
+          </div>
+        )}
+      </div>
+    );
+  }
+
   // ファイルリスト表示 - アイコン付きリスト
   if (format === "application/x-file-list") {
     const fileList = parseFileList(content);
@@ -68,8 +152,13 @@ export function ClipboardContentRenderer({ format, content, isExpanded }: Clipbo
     );
   }
 
-  // 画像表示
+  // 画像表示（contentはサムネイルの場合があるため、別ウィンドウには元画像を表示）
   if (format === "image/png") {
+    const openImageWindow = async (clickEvent: React.MouseEvent) => {
+      const fullContent = itemId ? await historyApi.getFullContent(itemId, format) : null;
+      showImageWindow(fullContent ?? content, clickEvent);
+    };
+
     return (
       <div className="text-sm">
         {content.startsWith("data:image/") ? (
@@ -89,7 +178,7 @@ export function ClipboardContentRenderer({ format, content, isExpanded }: Clipbo
                   e.screenX,
                   e.screenY,
                 );
-                showImageWindow(content, e);
+                openImageWindow(e);
               }}
               onKeyDown={(e) => {
                 if (e.key === "Enter" || e.key === " ") {
@@ -100,7 +189,7 @@ export function ClipboardContentRenderer({ format, content, isExpanded }: Clipbo
                     screenX: window.screen.availWidth / 2,
                     screenY: window.screen.availHeight / 2,
                   } as React.MouseEvent;
-                  showImageWindow(content, centerEvent);
+                  openImageWindow(centerEvent);
                 }
               }}
               onError={(e) => {
@@ -109,7 +198,11 @@ export function ClipboardContentRenderer({ format, content, isExpanded }: Clipbo
               }}
               title="クリックで別ウィンドウ表示"
             />
-            <p className="text-xs text-muted-foreground">画像データ ({Math.round(content.length / 1024)}KB)</p>
+            <p className="text-xs text-muted-foreground">
+              {imageMetadata
+                ? `画像データ (${imageMetadata.width}×${imageMetadata.height}, ${Math.round(imageMetadata.byte_size / 1024)}KB)`
+                : `画像データ (${Math.round(content.length / 1024)}KB)`}
+            </p>
           </div>
         ) : (
           <p className="text-muted-foreground">{displayContent}</p>
@@ -2,11 +2,13 @@ import { Clock, Copy, Hash } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card } from "@/components/ui/card";
 import { FormatBadges } from "@/components/ui/format-badges";
-import { useImageCopy } from "@/hooks/useImageCopy";
+import { hasExtraFormats, useItemRestore } from "@/hooks/useItemRestore";
 import { useTextCopy } from "@/hooks/useTextCopy";
 import type { DisplayClipboardItem } from "@/types/clipboardActions";
+import { getPreviewFormat } from "@/utils/clipboard/itemUtils";
 import { formatRelativeTime } from "@/utils/dateUtils";
-import { getTypeIcon, getTypeName } from "@/utils/textUtils";
+import { historyApi } from "@/utils/tauri-api";
+import { formatFileSize, getKindName, getTypeIcon, getTypeName } from "@/utils/textUtils";
 import { ClipboardContentRenderer } from "./ClipboardContentRenderer";
 
 interface ClipboardItemProps {
@@ -30,8 +32,10 @@ export function ClipboardItem({
   onFormatChange,
   onContextMenu,
 }: ClipboardItemProps) {
-  const { copyImageToClipboard } = useImageCopy();
   const { copyTextToClipboard } = useTextCopy();
+  const { restoreItemToClipboard } = useItemRestore();
+  // 内容の種類はメイン形式のテキストに対する判定結果
+  const kindName = currentFormat === item.content_type ? getKindName(item.kind, item.kind_detail) : null;
   return (
     <Card
       key={item.id}
@@ -59,10 +63,39 @@ export function ClipboardItem({
               <span className="text-xs px-1.5 py-0.5 bg-muted rounded text-muted-foreground">
                 {getTypeName(currentFormat)}
               </span>
+              {kindName && (
+                <span
+                  className="text-xs px-1.5 py-0.5 bg-muted rounded text-muted-foreground"
+                  title={`判定の確信度: ${Math.round((item.kind_confidence ?? 0) * 100)}%`}
+                >
+                  {kindName}
+                </span>
+              )}
+              {item.truncated_formats?.[currentFormat] !== undefined && (
+                <span
+                  className="text-xs px-1.5 py-0.5 bg-muted rounded text-muted-foreground"
+                  title={`元のサイズ: ${formatFileSize(item.truncated_formats[currentFormat])}`}
+                >
+                  切り詰め
+                </span>
+              )}
+              {item.tags?.map((tag) => (
+                <span key={tag} className="text-xs px-1.5 py-0.5 border rounded text-muted-foreground">
+                  #{tag}
+                </span>
+              ))}
             </div>
 
             {/* コンテンツ表示 - 形式別の特殊表示 */}
-            <ClipboardContentRenderer format={currentFormat} content={currentContent} isExpanded={isExpanded} />
+            <ClipboardContentRenderer
+              itemId={item.id}
+              format={currentFormat}
+              kind={currentFormat === item.content_type ? item.kind : undefined}
+              content={currentContent}
+              isExpanded={isExpanded}
+              imageMetadata={item.image_metadata}
+              files={currentFormat === item.content_type ? item.files : undefined}
+            />
           </button>
 
           {/* 複数形式バッジ - ボタン外に移動 */}
@@ -72,6 +105,7 @@ export function ClipboardItem({
               currentFormat={currentFormat}
               onFormatChange={(format) => onFormatChange(item.id, format)}
               mainFormat={item.content_type}
+              derivedFormats={item.derived_formats}
             />
           )}
         </div>
@@ -84,11 +118,22 @@ export function ClipboardItem({
             onClick={async (e) => {
               e.stopPropagation();
 
-              // 画像データの場合は画像としてコピー、それ以外はテキストとしてコピー
-              if (currentFormat === "image/png" && currentContent.startsWith("data:image/")) {
-                await copyImageToClipboard(currentContent);
+              // アプリ固有の形式を含む場合・HTML/RTFのテキストを表示中の場合・画像の場合は全形式を書き戻し、
+              // それ以外はテキストとしてコピー
+              const isPreviewOfMarkup =
+                currentFormat !== item.content_type && currentFormat === getPreviewFormat(item);
+              // 一覧の画像はサムネイルのため、保存済みの元画像を書き戻す
+              const isImage = currentFormat === "image/png" && currentContent.startsWith("data:image/");
+              if ((currentFormat === item.content_type && hasExtraFormats(item)) || isPreviewOfMarkup || isImage) {
+                await restoreItemToClipboard(item);
+              } else if (item.is_sensitive || item.partial_formats?.includes(currentFormat)) {
+                // 機密データ・大きなテキストは一覧では伏せられているか先頭部分のみのため、元の内容を取得してコピー
+                const fullContent = await historyApi.getFullContent(item.id, currentFormat);
+                if (fullContent !== null) {
+                  await copyTextToClipboard(fullContent, item.id);
+                }
               } else {
-                await copyTextToClipboard(currentContent);
+                await copyTextToClipboard(currentContent, item.id);
               }
             }}
             title={
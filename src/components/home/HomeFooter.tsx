@@ -21,19 +21,34 @@ export function HomeFooter({ clipboardItems, loading, clipboard, onHistoryReload
     return () => clearInterval(syncInterval);
   }, [clipboard.syncMonitoringStatus]);
   const handleStartMonitoring = () => {
-    clipboard
-      .startMonitoring((newText: string) => {
-        console.log("🔄 手動監視: クリップボード変更検出:", newText.substring(0, 50));
-        // 少し遅延してUI更新（データベース保存の完了を待つ）
-        setTimeout(() => {
-          onHistoryReload().catch(console.error);
-        }, 100);
-      })
-      .catch((err) => {
-        console.error("手動監視開始エラー:", err);
-      });
+    clipboard.startMonitoring().catch((err) => {
+      console.error("手動監視開始エラー:", err);
+    });
   };
 
+  const health = clipboard.health;
+  const isRestarting = clipboard.isMonitoring && !!health?.restarting;
+  const monitoringLabel = !clipboard.isMonitoring
+    ? "停止中"
+    : isRestarting
+      ? "再起動待ち"
+      : !clipboard.isPaused
+        ? "監視中"
+        : clipboard.pausedUntil
+          ? `一時停止中（${new Date(clipboard.pausedUntil).toLocaleTimeString()}まで）`
+          : "一時停止中（再起動まで）";
+
+  // 稼働状況の詳細（ホバーで表示）
+  const healthDetails = health
+    ? [
+        `最後の記録: ${health.last_capture_at ? new Date(health.last_capture_at).toLocaleString() : "なし"}`,
+        `再起動回数: ${health.restart_count}`,
+        health.last_error ? `直近のエラー: ${health.last_error}` : null,
+      ]
+        .filter(Boolean)
+        .join("\n")
+    : undefined;
+
   return (
     <div className="flex-shrink-0 border-t bg-card p-2">
       <div className="flex justify-between items-center">
@@ -42,9 +57,27 @@ export function HomeFooter({ clipboardItems, loading, clipboard, onHistoryReload
             <RefreshCw className={`h-3 w-3 mr-1 ${loading ? "animate-spin" : ""}`} />
             更新
           </Button>
-          <div className="flex items-center gap-1 text-xs">
-            <span className={`w-2 h-2 rounded-full ${clipboard.isMonitoring ? "bg-green-500" : "bg-red-500"}`}></span>
-            <span className="text-muted-foreground">{clipboard.isMonitoring ? "監視中" : "停止中"}</span>
+          <div className="flex items-center gap-1 text-xs" title={healthDetails}>
+            <span
+              className={`w-2 h-2 rounded-full ${
+                !clipboard.isMonitoring || isRestarting
+                  ? "bg-red-500"
+                  : clipboard.isPaused
+                    ? "bg-yellow-500"
+                    : "bg-green-500"
+              }`}
+            ></span>
+            <span className="text-muted-foreground">{monitoringLabel}</span>
+            {clipboard.isMonitoring && clipboard.isPaused && (
+              <Button
+                variant="outline"
+                size="sm"
+                className="ml-2 h-6 px-2 text-xs"
+                onClick={() => clipboard.resumeMonitoring().catch(console.error)}
+              >
+                再開
+              </Button>
+            )}
             {!clipboard.isMonitoring && (
               <Button variant="outline" size="sm" className="ml-2 h-6 px-2 text-xs" onClick={handleStartMonitoring}>
                 開始
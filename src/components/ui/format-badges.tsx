@@ -6,9 +6,10 @@ interface FormatBadgeProps {
   isActive: boolean;
   onClick: () => void;
   isMain?: boolean;
+  isDerived?: boolean;
 }
 
-function FormatBadge({ format, isActive, onClick, isMain = false }: FormatBadgeProps) {
+function FormatBadge({ format, isActive, onClick, isMain = false, isDerived = false }: FormatBadgeProps) {
   return (
     <button
       type="button"
@@ -22,10 +23,11 @@ function FormatBadge({ format, isActive, onClick, isMain = false }: FormatBadgeP
         ${isMain ? "ring-1 ring-primary/20" : ""}
       `}
       onClick={onClick}
-      title={`${getTypeName(format)}として表示`}
+      title={`${getTypeName(format)}として表示${isDerived ? "（HTML/RTFから変換）" : ""}`}
     >
       <span className="text-xs">{getTypeIcon(format)}</span>
       <span className="font-medium">{getTypeName(format)}</span>
+      {isDerived && <span className="opacity-70">（変換）</span>}
     </button>
   );
 }
@@ -35,9 +37,16 @@ interface FormatBadgesProps {
   currentFormat: string;
   onFormatChange: (format: string) => void;
   mainFormat?: string;
+  derivedFormats?: string[];
 }
 
-export function FormatBadges({ availableFormats, currentFormat, onFormatChange, mainFormat }: FormatBadgesProps) {
+export function FormatBadges({
+  availableFormats,
+  currentFormat,
+  onFormatChange,
+  mainFormat,
+  derivedFormats = [],
+}: FormatBadgesProps) {
   // 形式が1つしかない場合は表示しない
   if (availableFormats.length <= 1) {
     return null;
@@ -52,6 +61,7 @@ export function FormatBadges({ availableFormats, currentFormat, onFormatChange,
           isActive={format === currentFormat}
           onClick={() => onFormatChange(format)}
           isMain={format === mainFormat}
+          isDerived={derivedFormats.includes(format)}
         />
       ))}
     </div>
@@ -1,26 +1,39 @@
 import { invoke } from "@tauri-apps/api/core";
-import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { listen } from "@tauri-apps/api/event";
 import { useCallback, useEffect, useState } from "react";
-import type { ClipboardItem } from "@/types/clipboard";
+import type {
+  AppEventPayload,
+  ClipboardItem,
+  MonitoringStatus,
+  PauseDuration,
+  WatcherHealth,
+} from "@/types/clipboard";
 
 export interface ClipboardHook {
   currentText: string;
   isMonitoring: boolean;
+  isPaused: boolean;
+  pausedUntil: number | null;
+  health: WatcherHealth | null; // ウォッチャーの稼働状況（最後の記録時刻・エラー・再起動回数）
   readClipboard: () => Promise<string>;
   writeClipboard: (text: string) => Promise<void>;
-  startMonitoring: (onUpdate?: (text: string) => void) => Promise<void>;
+  startMonitoring: () => Promise<void>;
   stopMonitoring: () => Promise<void>;
   hasClipboardText: () => Promise<boolean>;
   clearClipboard: () => Promise<void>;
   syncMonitoringStatus: () => Promise<void>;
+  pauseMonitoring: (duration: PauseDuration) => Promise<void>;
+  resumeMonitoring: () => Promise<void>;
   error: string | null;
 }
 
 export function useClipboard(): ClipboardHook {
   const [currentText, setCurrentText] = useState<string>("");
   const [isMonitoring, setIsMonitoring] = useState<boolean>(false);
+  const [isPaused, setIsPaused] = useState<boolean>(false);
+  const [pausedUntil, setPausedUntil] = useState<number | null>(null);
+  const [health, setHealth] = useState<WatcherHealth | null>(null);
   const [error, setError] = useState<string | null>(null);
-  const [eventUnlisten, setEventUnlisten] = useState<UnlistenFn | null>(null);
 
   const clearError = useCallback(() => setError(null), []);
 
@@ -75,123 +88,106 @@ export function useClipboard(): ClipboardHook {
     }
   }, [clearError]);
 
-  const startMonitoring = useCallback(
-    async (onUpdate?: (text: string) => void): Promise<void> => {
-      try {
-        clearError();
-
-        // 監視状態を確認
-        const currentStatus = await invoke<boolean>("get_monitoring_status");
-
-        if (currentStatus) {
-          console.log("クリップボード監視は既に開始されています - 状態を同期");
-          setIsMonitoring(true);
-
-          // イベントリスナーが設定されていない場合は設定
-          if (!eventUnlisten) {
-            const unlisten = await listen<ClipboardItem>("clipboard-updated", (event) => {
-              console.log("📨 Rustからclipboard-updatedイベント受信:", event.payload);
-              const item = event.payload;
-              setCurrentText(item.content);
-              onUpdate?.(item.content);
-            });
-            setEventUnlisten(() => unlisten);
-          }
-          return;
-        }
-
-        console.log("🚀 Rustイベントベースのクリップボード監視を開始...");
-
-        // Rustのイベントをリッスン
-        const unlisten = await listen<ClipboardItem>("clipboard-updated", (event) => {
-          console.log("📨 Rustからclipboard-updatedイベント受信:", event.payload);
-          const item = event.payload;
-          setCurrentText(item.content);
-          onUpdate?.(item.content);
-        });
-
-        setEventUnlisten(() => unlisten);
-
-        // Rust側で監視開始
-        await invoke("start_clipboard_monitoring");
-
-        // 監視状態を確認
-        const monitoringStatus = await invoke<boolean>("get_monitoring_status");
-        setIsMonitoring(monitoringStatus);
-
-        console.log("✅ Rustイベントベースのクリップボード監視を開始しました");
-      } catch (err) {
-        const errorMsg = `クリップボード監視開始エラー: ${err}`;
-        console.error("❌", errorMsg);
-        setError(errorMsg);
-        throw new Error(errorMsg);
-      }
-    },
-    [eventUnlisten, clearError],
-  );
+  // 監視はバックエンドが起動時に開始する。ここでは停止後の手動での開始・停止のみ行い、状態はイベントで反映する
+  const startMonitoring = useCallback(async (): Promise<void> => {
+    try {
+      clearError();
+      await invoke("start_clipboard_monitoring");
+    } catch (err) {
+      const errorMsg = `クリップボード監視開始エラー: ${err}`;
+      console.error("❌", errorMsg);
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [clearError]);
 
   const stopMonitoring = useCallback(async (): Promise<void> => {
     try {
       clearError();
-
-      if (!isMonitoring) {
-        console.log("クリップボード監視は既に停止されています");
-        return;
-      }
-
-      console.log("🛑 Rustイベントベースのクリップボード監視を停止...");
-
-      // Rust側で監視停止
       await invoke("stop_clipboard_monitoring");
-
-      // イベントリスナーを停止
-      if (eventUnlisten) {
-        eventUnlisten();
-        setEventUnlisten(null);
-      }
-
-      setIsMonitoring(false);
-      console.log("✅ クリップボード監視を停止しました");
     } catch (err) {
       const errorMsg = `クリップボード監視停止エラー: ${err}`;
       setError(errorMsg);
       console.error(errorMsg);
-      // エラーでも状態をリセット
-      setIsMonitoring(false);
-      setEventUnlisten(null);
     }
-  }, [isMonitoring, eventUnlisten, clearError]);
+  }, [clearError]);
 
   const syncMonitoringStatus = useCallback(async (): Promise<void> => {
     try {
-      const status = await invoke<boolean>("get_monitoring_status");
-      setIsMonitoring(status);
+      const status = await invoke<MonitoringStatus>("get_monitoring_status");
+      setIsMonitoring(status.active);
+      setIsPaused(status.paused);
+      setPausedUntil(status.paused_until);
+      setHealth(status.health);
       console.log("🔄 監視状態同期:", status);
     } catch (err) {
       console.error("監視状態同期エラー:", err);
     }
   }, []);
 
+  const pauseMonitoring = useCallback(
+    async (duration: PauseDuration): Promise<void> => {
+      try {
+        clearError();
+        await invoke("pause_clipboard_monitoring", { duration });
+        await syncMonitoringStatus();
+      } catch (err) {
+        const errorMsg = `クリップボード監視一時停止エラー: ${err}`;
+        setError(errorMsg);
+        throw new Error(errorMsg);
+      }
+    },
+    [clearError, syncMonitoringStatus],
+  );
+
+  const resumeMonitoring = useCallback(async (): Promise<void> => {
+    try {
+      clearError();
+      await invoke("resume_clipboard_monitoring");
+      await syncMonitoringStatus();
+    } catch (err) {
+      const errorMsg = `クリップボード監視再開エラー: ${err}`;
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [clearError, syncMonitoringStatus]);
+
+  // トレイからの一時停止や期限到達による自動再開、他のウィンドウでの開始・停止を反映
+  useEffect(() => {
+    const unlisten = listen<AppEventPayload<"monitoring-state-changed">>("monitoring-state-changed", (event) => {
+      setIsMonitoring(event.payload.active);
+      setIsPaused(event.payload.paused);
+      setPausedUntil(event.payload.paused_until);
+      setHealth(event.payload.health);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // 初期化時に監視状態を確認
   useEffect(() => {
     syncMonitoringStatus();
   }, [syncMonitoringStatus]);
 
-  // コンポーネントアンマウント時の自動クリーンアップ
+  // 最後にキャプチャされた内容を反映
   useEffect(() => {
+    const unlisten = listen<ClipboardItem>("clipboard-updated", (event) => {
+      setCurrentText(event.payload.content);
+    });
+
     return () => {
-      if (eventUnlisten) {
-        eventUnlisten();
-      }
-      if (isMonitoring) {
-        stopMonitoring().catch(console.error);
-      }
+      unlisten.then((fn) => fn());
     };
-  }, [isMonitoring, eventUnlisten, stopMonitoring]);
+  }, []);
 
   return {
     currentText,
     isMonitoring,
+    isPaused,
+    pausedUntil,
+    health,
     readClipboard,
     writeClipboard,
     startMonitoring,
@@ -199,6 +195,8 @@ export function useClipboard(): ClipboardHook {
     hasClipboardText,
     clearClipboard,
     syncMonitoringStatus,
+    pauseMonitoring,
+    resumeMonitoring,
     error,
   };
 }
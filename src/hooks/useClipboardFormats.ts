@@ -1,5 +1,6 @@
 import { useCallback, useState } from "react";
 import type { DisplayClipboardItem } from "@/types/clipboardActions";
+import { getPreviewFormat } from "@/utils/clipboard/itemUtils";
 
 /**
  * クリップボードアイテムのフォーマット管理フック
@@ -19,7 +20,7 @@ export function useClipboardFormats() {
   // アイテムの現在の形式とコンテンツを取得
   const getCurrentFormatAndContent = useCallback(
     (item: DisplayClipboardItem) => {
-      const selectedFormat = selectedFormats[item.id] || item.content_type;
+      const selectedFormat = selectedFormats[item.id] || getPreviewFormat(item);
       const content = item.format_contents?.[selectedFormat] || item.content;
       return { format: selectedFormat, content };
     },
@@ -0,0 +1,44 @@
+import { useCallback } from "react";
+import type { DisplayClipboardItem } from "@/types/clipboardActions";
+import { clipboardApi } from "@/utils/tauri-api";
+import { useClipboardControl } from "./useClipboardControl";
+
+// clipboard-rsの組み込み形式（これ以外はアプリ固有の追加形式）
+const BUILTIN_FORMATS = ["text/plain", "text/html", "text/rtf", "image/png", "application/x-file-list"];
+
+/**
+ * アプリ固有の追加形式（SVG・LibreOfficeなど）を含むか
+ */
+export function hasExtraFormats(item: DisplayClipboardItem): boolean {
+  return (item.available_formats ?? []).some(
+    (format) => format !== item.content_type && !BUILTIN_FORMATS.includes(format),
+  );
+}
+
+/**
+ * 保存済みアイテムの全形式をクリップボードに書き戻すカスタムフック（監視一時停止付き）
+ */
+export function useItemRestore() {
+  const { safeExecuteCopy } = useClipboardControl();
+
+  const restoreItemToClipboard = useCallback(
+    async (item: DisplayClipboardItem) => {
+      return await safeExecuteCopy(
+        item.content,
+        async () => {
+          try {
+            await clipboardApi.restoreItem(item.id);
+            console.log("✅ 全形式をクリップボードに書き戻しました");
+          } catch (error) {
+            console.error("❌ クリップボード書き戻しエラー:", error);
+            throw new Error("クリップボードへの書き戻しに失敗しました");
+          }
+        },
+        "item-restore",
+      );
+    },
+    [safeExecuteCopy],
+  );
+
+  return { restoreItemToClipboard };
+}
@@ -1,19 +1,21 @@
 import { useCallback } from "react";
+import { clipboardApi } from "@/utils/tauri-api";
 import { useClipboardControl } from "./useClipboardControl";
 
 /**
  * テキストデータをクリップボードにコピーするカスタムフック（監視一時停止付き）
+ * 履歴のアイテムのIDを渡すと、監視側で新しいコピーではなくそのアイテムの使用として記録される
  */
 export function useTextCopy() {
   const { safeExecuteCopy } = useClipboardControl();
 
   const copyTextToClipboard = useCallback(
-    async (text: string) => {
+    async (text: string, itemId?: string) => {
       return await safeExecuteCopy(
         text,
         async () => {
           try {
-            await navigator.clipboard.writeText(text);
+            await clipboardApi.setText(text, itemId);
             console.log("✅ テキストをクリップボードにコピーしました");
           } catch (error) {
             console.error("❌ テキストコピーエラー:", error);
@@ -9,6 +9,7 @@ import { ScrollArea } from "@/components/ui/scroll-area";
 import { useActions } from "@/contexts/ActionsContext";
 import { useClipboard } from "@/hooks/useClipboard";
 // import type { ClipboardItem } from "@/types/clipboard"; // 使用しない
+import type { AppEventPayload } from "@/types/clipboard";
 import type { ClipboardAction, ContextMenuState, DisplayClipboardItem } from "@/types/clipboardActions";
 import { convertToClipboardAction, searchActions } from "@/utils/clipboard/actionUtils";
 import { calculateMenuPosition } from "@/utils/menuPosition";
@@ -78,6 +79,8 @@ export default function Home() {
 
     // 直接clipboard-updatedイベントをリッスンして履歴リストを即座に更新
     let unlistenClipboardUpdated: (() => void) | null = null;
+    let unlistenItemDeleted: (() => void) | null = null;
+    let unlistenHistoryCleared: (() => void) | null = null;
     let unlistenNavigationEvents: (() => void) | null = null;
 
     const setupDirectEventListener = async () => {
@@ -86,21 +89,30 @@ export default function Home() {
           console.log("📨 直接受信: clipboard-updatedイベント:", event.payload);
           const newItem = event.payload; // 既にDisplayClipboardItem形式
 
-          // 履歴リストの先頭に新しいアイテムを追加
+          // 履歴リストの先頭に新しいアイテムを追加（再コピーされた既存アイテムは先頭に移動）
           setClipboardItems((prevItems) => {
-            // 重複チェック（同じIDまたは同じ内容）
-            const isDuplicate = prevItems.some((item) => item.id === newItem.id || item.content === newItem.content);
-
-            if (isDuplicate) {
-              console.log("⚠️ 重複アイテムのため履歴更新をスキップ");
-              return prevItems;
+            // 先頭に移動しない更新（ClipOneから書き戻した使用の記録など）はその場で置き換える
+            const existing = prevItems.find((item) => item.id === newItem.id);
+            if (existing && existing.timestamp === newItem.timestamp) {
+              return prevItems.map((item) => (item.id === newItem.id ? newItem : item));
             }
 
-            console.log("✅ 履歴リストに新しいアイテムを追加:", newItem.content.substring(0, 50));
-            return [newItem, ...prevItems];
+            const otherItems = prevItems.filter((item) => item.id !== newItem.id);
+
+            console.log("✅ 履歴リストの先頭にアイテムを追加:", newItem.content.substring(0, 50));
+            return [newItem, ...otherItems];
           });
         });
         console.log("✅ 直接clipboard-updatedイベントリスナー設定完了");
+
+        // 削除されたアイテム（他のウィンドウでの削除・有効期限切れ・整理）を一覧から除外
+        unlistenItemDeleted = await listen<AppEventPayload<"item-deleted">>("item-deleted", (event) => {
+          const deletedIds = new Set(event.payload.ids);
+          setClipboardItems((prevItems) => prevItems.filter((item) => !deletedIds.has(item.id)));
+        });
+        unlistenHistoryCleared = await listen<AppEventPayload<"history-cleared">>("history-cleared", () => {
+          setClipboardItems([]);
+        });
       } catch (err) {
         console.error("❌ 直接イベントリスナー設定エラー:", err);
       }
@@ -138,39 +150,21 @@ export default function Home() {
 
     setupNavigationEventListener();
 
-    // クリップボード監視開始（遅延）
-    const timer = setTimeout(() => {
-      console.log("🚀 クリップボード監視開始処理開始...");
-      console.log("📋 現在の監視状態:", clipboard.isMonitoring);
-
-      clipboard
-        .startMonitoring((newText: string) => {
-          console.log("🔄 onUpdateコールバック: クリップボード変更検出:", newText.substring(0, 50));
-          // このコールバックはバックアップとして保持（直接イベントリスナーが機能しない場合用）
-        })
-        .then(async () => {
-          console.log("✅ クリップボード監視が正常に開始されました");
-          // 状態を同期
-          await clipboard.syncMonitoringStatus();
-          console.log("📊 監視状態:", clipboard.isMonitoring);
-        })
-        .catch((err) => {
-          console.error("❌ クリップボード監視開始エラー:", err);
-          console.error("❌ エラー詳細:", err.toString());
-        });
-    }, 1000);
-
     // クリーンアップ
     return () => {
-      clearTimeout(timer);
       if (unlistenClipboardUpdated) {
         unlistenClipboardUpdated();
       }
+      if (unlistenItemDeleted) {
+        unlistenItemDeleted();
+      }
+      if (unlistenHistoryCleared) {
+        unlistenHistoryCleared();
+      }
       if (unlistenNavigationEvents) {
         unlistenNavigationEvents();
       }
       console.log("Home コンポーネントクリーンアップ");
-      clipboard.stopMonitoring().catch(console.error);
     };
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []); // 初期化時のみ実行（意図的に空の依存配列を使用）
@@ -269,9 +263,14 @@ export default function Home() {
   };
 
   // アクションを実行
-  const executeAction = (action: ClipboardAction, item: DisplayClipboardItem) => {
-    action.execute(item.content, navigate, item.id);
+  const executeAction = async (action: ClipboardAction, item: DisplayClipboardItem) => {
     closeContextMenu();
+
+    // 機密データは一覧では伏せられているため元の内容を取得して実行
+    const content = item.is_sensitive
+      ? ((await historyApi.getFullContent(item.id, item.content_type)) ?? item.content)
+      : item.content;
+    action.execute(content, navigate, item.id);
   };
 
   // アイテムに対して利用可能なアクションを取得
@@ -55,4 +55,182 @@ export interface AppSettings {
   theme: string;
   export_format: string;
   notifications_enabled: boolean;
+  duplicate_policy: DuplicatePolicy;
+  ignore_whitespace_duplicates: boolean; // 空白の差異を無視して重複判定
+  blob_threshold_kb: number; // これを超えるコンテンツはディスク上のBLOBストアに保存
+  concealed_policy: ConcealedPolicy;
+  concealed_placeholder_ttl_secs: number; // プレースホルダーを残す秒数
+  sensitive_detection_enabled: boolean;
+  sensitive_ttl_secs: number; // 機密データを自動削除するまでの秒数（0は削除しない）
+  sensitive_rules: SensitiveRule[]; // 組み込みルールに追加するユーザー定義ルール
+  app_capture_default_action: AppRuleAction; // どのアプリ別ルールにも一致しない場合の動作
+  app_capture_rules: AppCaptureRule[]; // 上から順に評価するアプリ別ルール
+  monitor_on_startup: boolean; // 起動時にクリップボード監視を開始（前回停止したまま終了した場合を除く）
+  watcher_backend: WatcherBackend;
+  polling_interval_ms: number; // ポーリング監視の確認間隔
+  primary_selection_enabled: boolean; // X11のPRIMARYセレクション（テキスト選択）も記録
+  primary_selection_debounce_ms: number; // 選択操作が落ち着いてから記録するまでの待ち時間
+  extra_capture_formats: string[]; // 追加で保存する形式（末尾の*で前方一致）
+  derive_markdown_from_html: boolean; // HTMLからMarkdownも派生させる（テキストは常に派生）
+  capture_coalesce_window_ms: number; // この時間内の連続した変更を1つのアイテムにまとめる（0は無効）
+  capture_rate_limit_per_minute: number; // コピー元アプリごとの1分あたりの記録数の上限（0は無制限）
+  move_reused_item_to_top: boolean; // ClipOneから書き戻したアイテムを履歴の先頭に移動
+  capture_rules: CaptureRule[]; // 上から順に評価するキャプチャルール（タグ付け・変換など）
+  content_size_limits: SizeLimit[]; // 形式ごとのサイズ上限（上から順に最初に一致したもの）
+  log_level: LogLevel; // ログの出力レベル（debug以上で取得した内容のサイズ・ハッシュも出力）
 }
+
+// ログの出力レベル
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+// 重複コンテンツの扱い
+export type DuplicatePolicy = "keep_all" | "move_to_top" | "ignore";
+
+// 機密データ検出ルール
+export interface SensitiveRule {
+  name: string;
+  pattern: string; // 正規表現
+  validator?: "luhn" | "credit_card" | "pem" | null;
+  enabled: boolean;
+}
+
+// パスワードマネージャーの機密ヒント付きコピーの扱い
+export type ConcealedPolicy = "skip" | "redact";
+
+// アプリ別キャプチャルール
+export type AppRuleAction = "allow" | "block";
+
+export interface AppCaptureRule {
+  id: string; // 追加時は空文字列
+  action: AppRuleAction;
+  field: "executable" | "wm_class" | "window_title";
+  pattern: string; // window_titleの場合は正規表現
+  enabled: boolean;
+}
+
+export interface AppCaptureRules {
+  default_action: AppRuleAction;
+  rules: AppCaptureRule[];
+}
+
+// サイズ上限を超えた形式の扱い（truncateはバイナリ形式では保存しない）
+export type OversizePolicy = "skip" | "truncate" | "offload";
+
+// 形式ごとのサイズ上限
+export interface SizeLimit {
+  format: string; // 形式（末尾の*で前方一致）
+  max_kb: number;
+  policy: OversizePolicy;
+}
+
+// キャプチャルールの条件（指定した条件をすべて満たす場合に一致）
+export interface RuleConditions {
+  pattern?: string | null; // プライマリ形式のテキストの正規表現
+  kinds?: ContentKind[]; // 内容の種類（いずれか）
+  formats?: string[]; // 含む形式（いずれか、末尾の*で前方一致）
+  min_size?: number | null; // プライマリ形式のサイズの下限（バイト）
+  max_size?: number | null; // プライマリ形式のサイズの上限（バイト）
+  source_apps?: string[]; // コピー元のアプリ名・実行ファイル名（いずれか）
+}
+
+// キャプチャルールで適用するテキストの変換
+export type TextTransform = "trim" | "collapse_whitespace" | "strip_tracking_params";
+
+// キャプチャルールの動作
+export type RuleAction =
+  | { type: "drop" }
+  | { type: "tag"; tag: string }
+  | { type: "favorite" }
+  | { type: "set_ttl"; ttl_secs: number }
+  | { type: "transform"; transform: TextTransform };
+
+export interface CaptureRule {
+  id: string; // 追加時は空文字列
+  name: string;
+  conditions: RuleConditions;
+  actions: RuleAction[];
+  stop_processing: boolean; // 一致した場合に以降のルールを評価しない
+  enabled: boolean;
+}
+
+// キャプチャルールを評価した結果
+export interface RuleOutcome {
+  matched_rules: string[]; // 一致したルールのID（評価順）
+  dropped: boolean;
+  tags: string[];
+  favorite: boolean;
+  ttl_secs: number | null;
+  transformed: boolean;
+}
+
+// キャプチャルールの試行結果
+export interface CaptureRuleTestResult {
+  outcome: RuleOutcome;
+  kind: ContentKind | null; // 評価後のテキストの内容の種類
+  text: string; // 変換を適用した後のテキスト
+}
+
+// クリップボード監視の方式（autoは変更イベントが使えない場合にポーリングへ切り替え）
+export type WatcherBackend = "auto" | "event" | "polling";
+
+// 取得元のセレクション（primaryはX11のテキスト選択）
+export type ClipboardSelection = "clipboard" | "primary";
+
+// テキストの内容の種類（MIMEタイプとは別に判定される）
+export type ContentKind =
+  | "plain_text"
+  | "url"
+  | "email"
+  | "phone"
+  | "uuid"
+  | "ip_address"
+  | "color"
+  | "file_path"
+  | "data_url"
+  | "json"
+  | "yaml"
+  | "xml"
+  | "html"
+  | "rtf"
+  | "sql"
+  | "markdown"
+  | "code";
+
+// キャプチャの一時停止期間
+export type PauseDuration = "five_minutes" | "one_hour" | "until_restart";
+
+export interface PauseStatus {
+  paused: boolean;
+  paused_until: number | null; // 自動再開の予定時刻（ミリ秒、再起動までの場合はnull）
+}
+
+// ウォッチャーの稼働状況
+export interface WatcherHealth {
+  restarting: boolean; // ウォッチャーが終了し、再起動を待っている
+  restart_count: number; // 起動してからウォッチャーを再起動した回数
+  last_capture_at: number | null; // 最後にアイテムを記録した時刻（ミリ秒）
+  last_error: string | null;
+  last_error_at: number | null; // 最後のエラーの発生時刻（ミリ秒）
+}
+
+// 監視状態（get_monitoring_statusの戻り値）
+export interface MonitoringStatus extends PauseStatus {
+  active: boolean; // 監視中か（ウォッチャーの再起動待ちを含む）
+  backend: Exclude<WatcherBackend, "auto"> | null; // 動作中の監視方式
+  dropped_events: number;
+  health: WatcherHealth;
+}
+
+// アイテムが削除された理由
+export type DeleteReason = "user" | "expired" | "cleanup";
+
+// バックエンドから通知される変更イベント（typeと同名のイベントとして送信、内容は含まずIDのみ）
+export type AppEvent =
+  | { type: "item-added"; ids: string[] }
+  | { type: "item-updated"; ids: string[] }
+  | { type: "item-deleted"; ids: string[]; reason: DeleteReason }
+  | { type: "history-cleared" }
+  | ({ type: "monitoring-state-changed" } & MonitoringStatus)
+  | { type: "settings-changed"; key: string | null }; // keyがnullの場合は設定全体
+
+export type AppEventPayload<T extends AppEvent["type"]> = Extract<AppEvent, { type: T }>;
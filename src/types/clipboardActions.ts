@@ -1,3 +1,5 @@
+import type { ClipboardSelection, ContentKind } from "@/types/clipboard";
+
 /**
  * クリップボードアクション型定義
  */
@@ -22,9 +24,52 @@ export interface DisplayClipboardItem {
   timestamp: number; // Dateからnumberに変更（Unix timestamp）
   is_favorite: boolean;
   source_app?: string; // appからsource_appに変更
+  source_executable?: string; // コピー元の実行ファイル
+  source_window_title?: string; // コピー時のウィンドウタイトル
   created_at: string; // DateTime UTCの文字列表現
   available_formats?: string[];
   format_contents?: Record<string, string>;
+  copy_count: number; // 同一内容がコピーされた回数
+  last_used_at?: number; // ClipOneから最後に書き戻した時刻（Unix timestamp）
+  use_count?: number; // ClipOneから書き戻した回数
+  image_metadata?: ImageMetadata; // 画像の場合のみ（一覧ではcontentはサムネイル）
+  expires_at?: number; // 自動削除される時刻（Unix timestamp）
+  is_sensitive?: boolean; // 機密データ（一覧ではcontentは伏せられている）
+  sensitive_kind?: string; // 一致した機密データ検出ルール
+  selection?: ClipboardSelection; // 取得元のセレクション
+  kind?: ContentKind; // テキストの内容の種類（テキスト以外は未設定）
+  kind_confidence?: number; // 種類の判定の確信度（0.0〜1.0）
+  kind_detail?: string; // 種類の補足情報（コードの言語など）
+  files?: FileEntry[]; // ファイル・ファイルパスの場合の各ファイルの情報
+  derived_formats?: string[]; // HTML・RTFから派生させた形式（元のアプリが提供したものではない）
+  tags?: string[]; // キャプチャルールで付けたタグ
+  truncated_formats?: Record<string, number>; // サイズ上限により切り詰めた形式と元のサイズ（バイト）
+  partial_formats?: string[]; // 先頭部分のみ含む形式（全体はgetFullContentで取得）
+}
+
+/**
+ * ファイル情報型定義（取得時・再確認時点の状態）
+ */
+export interface FileEntry {
+  path: string; // 絶対パス
+  exists: boolean;
+  is_dir: boolean;
+  size: number | null; // バイト数（ディレクトリ・存在しない場合はnull）
+  modified_at: number | null; // 更新日時（Unix timestamp）
+  mime_type: string | null;
+  checked_at: number; // 最後に確認した時刻（Unix timestamp）
+}
+
+/**
+ * 画像メタデータ型定義
+ */
+export interface ImageMetadata {
+  width: number;
+  height: number;
+  pixel_format: string;
+  byte_size: number;
+  thumbnail_width?: number;
+  thumbnail_height?: number;
 }
 
 /**
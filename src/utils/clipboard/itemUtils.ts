@@ -66,3 +66,16 @@ export function convertClipboardItem(item: ClipboardItem): DisplayClipboardItem
     format_contents: Object.keys(formatContents).length > 0 ? formatContents : undefined,
   };
 }
+
+/**
+ * 一覧で最初に表示する形式を取得
+ *
+ * HTML・RTFのみのアイテムはマークアップの代わりに派生させたテキストを表示する。
+ */
+export function getPreviewFormat(item: DisplayClipboardItem): string {
+  const isMarkup = item.content_type === "text/html" || item.content_type === "text/rtf";
+  if (isMarkup && item.derived_formats?.includes("text/plain")) {
+    return "text/plain";
+  }
+  return item.content_type;
+}
@@ -1,14 +1,34 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { AppSettings, ClipboardItem, ClipboardStats, ExportFormat } from "@/types/clipboard";
-import type { DisplayClipboardItem } from "@/types/clipboardActions";
+import type {
+  AppCaptureRule,
+  AppCaptureRules,
+  AppRuleAction,
+  AppSettings,
+  CaptureRule,
+  CaptureRuleTestResult,
+  ClipboardItem,
+  ClipboardSelection,
+  ClipboardStats,
+  ExportFormat,
+  MonitoringStatus,
+  PauseDuration,
+  PauseStatus,
+} from "@/types/clipboard";
+import type { DisplayClipboardItem, FileEntry } from "@/types/clipboardActions";
 
 // クリップボード操作API
 export const clipboardApi = {
   // クリップボード読み取り
   getText: (): Promise<string> => invoke("get_clipboard_text"),
 
-  // クリップボード書き込み
-  setText: (text: string): Promise<void> => invoke("set_clipboard_text", { text }),
+  // クリップボード書き込み（履歴のアイテムのIDを渡すと新しいコピーではなく使用として記録）
+  setText: (text: string, itemId?: string): Promise<void> => invoke("set_clipboard_text", { text, itemId }),
+
+  // 現在のクリップボードを記録した場合に保存される形式（追加形式・派生形式を含む）
+  getFormats: (): Promise<string[]> => invoke("get_clipboard_formats"),
+
+  // 保存済みアイテムの全形式（アプリ固有の追加形式を含む）を書き戻し
+  restoreItem: (id: string): Promise<void> => invoke("restore_clipboard_item", { id }),
 
   // 監視開始/停止
   startMonitoring: (): Promise<void> => invoke("start_clipboard_monitoring"),
@@ -27,25 +47,40 @@ export const clipboardApi = {
 
   stopMonitoringService: (): Promise<void> => invoke("stop_clipboard_monitoring_service"),
 
-  getMonitoringStatus: (): Promise<boolean> => invoke("get_clipboard_monitoring_status"),
+  getMonitoringStatus: (): Promise<MonitoringStatus> => invoke("get_monitoring_status"),
+
+  // 一時停止/再開
+  pauseMonitoring: (duration: PauseDuration): Promise<PauseStatus> =>
+    invoke("pause_clipboard_monitoring", { duration }),
+
+  resumeMonitoring: (): Promise<PauseStatus> => invoke("resume_clipboard_monitoring"),
 };
 
 // 履歴管理API
 export const historyApi = {
   // 履歴取得（正規化されたデータベース用）
-  getHistory: (limit?: number, offset?: number): Promise<DisplayClipboardItem[]> =>
-    invoke("get_clipboard_history", { limit: limit || null, offset: offset || null }),
+  getHistory: (limit?: number, offset?: number, selection?: ClipboardSelection): Promise<DisplayClipboardItem[]> =>
+    invoke("get_clipboard_history", { limit: limit || null, offset: offset || null, selection: selection ?? null }),
 
   // 履歴検索（正規化されたデータベース用）
-  searchHistory: (query: string, limit?: number): Promise<DisplayClipboardItem[]> =>
-    invoke("search_clipboard_history", { query, limit }),
+  searchHistory: (query: string, limit?: number, selection?: ClipboardSelection): Promise<DisplayClipboardItem[]> =>
+    invoke("search_clipboard_history", { query, limit, selection: selection ?? null }),
 
   // 特定アイテム取得（正規化されたデータベース用）
   getItem: (id: string): Promise<DisplayClipboardItem | null> => invoke("get_clipboard_item", { id }),
 
+  // 一覧の画像はサムネイルのため、元データが必要な場合はIDで取得し直す
+  getFullContent: async (id: string, format: string): Promise<string | null> => {
+    const item = await invoke<DisplayClipboardItem | null>("get_clipboard_item", { id });
+    return item?.format_contents?.[format] ?? null;
+  },
+
   // お気に入り切り替え
   toggleFavorite: (id: string): Promise<boolean> => invoke("toggle_favorite", { id }),
 
+  // ファイルの存在・サイズなどを再確認
+  revalidateFiles: (id: string): Promise<FileEntry[]> => invoke("revalidate_item_files", { id }),
+
   // アイテム削除
   deleteItem: (id: string): Promise<void> => invoke("delete_clipboard_item", { id }),
 
@@ -72,6 +107,47 @@ export const settingsApi = {
 
   // 設定リセット
   resetSettings: (): Promise<AppSettings> => invoke("reset_settings"),
+
+  // 直近のログ取得（古い順、既定は500行）
+  getRecentLogs: (limit?: number): Promise<string[]> => invoke("get_recent_logs", { limit }),
+};
+
+// アプリ別キャプチャルールAPI
+export const appRulesApi = {
+  // ルール一覧取得
+  getRules: (): Promise<AppCaptureRules> => invoke("get_app_capture_rules"),
+
+  // ルール追加・更新（idが空の場合は追加）
+  saveRule: (rule: AppCaptureRule): Promise<AppCaptureRules> => invoke("save_app_capture_rule", { rule }),
+
+  // ルール削除
+  deleteRule: (id: string): Promise<AppCaptureRules> => invoke("delete_app_capture_rule", { id }),
+
+  // 評価順の並べ替え
+  reorderRules: (ids: string[]): Promise<AppCaptureRules> => invoke("reorder_app_capture_rules", { ids }),
+
+  // どのルールにも一致しない場合の動作
+  setDefaultAction: (action: AppRuleAction): Promise<AppCaptureRules> =>
+    invoke("set_app_capture_default_action", { action }),
+};
+
+// キャプチャルールAPI
+export const captureRulesApi = {
+  // ルール一覧取得
+  getRules: (): Promise<CaptureRule[]> => invoke("get_capture_rules"),
+
+  // ルール追加・更新（idが空の場合は追加）
+  saveRule: (rule: CaptureRule): Promise<CaptureRule[]> => invoke("save_capture_rule", { rule }),
+
+  // ルール削除
+  deleteRule: (id: string): Promise<CaptureRule[]> => invoke("delete_capture_rule", { id }),
+
+  // 評価順の並べ替え
+  reorderRules: (ids: string[]): Promise<CaptureRule[]> => invoke("reorder_capture_rules", { ids }),
+
+  // テキストに対して試行（ruleを省略すると保存済みの全ルール）
+  testRule: (text: string, rule?: CaptureRule, sourceApp?: string): Promise<CaptureRuleTestResult> =>
+    invoke("test_capture_rule", { text, rule, sourceApp }),
 };
 
 // エクスポート/インポートAPI
@@ -102,6 +178,8 @@ export const tauriApi = {
   history: historyApi,
   settings: settingsApi,
   export: exportApi,
+  appRules: appRulesApi,
+  captureRules: captureRulesApi,
 };
 
 export default tauriApi;
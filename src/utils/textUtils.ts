@@ -1,3 +1,5 @@
+import type { ContentKind } from "@/types/clipboard";
+
 /**
  * テキストを指定された長さで切り詰める
  */
@@ -14,6 +16,7 @@ export function getTypeIcon(type: string): string {
     case "text/html":
       return "🌐";
     case "text/rtf":
+    case "text/markdown":
       return "📝";
     case "text/uri-list":
       return "🔗";
@@ -39,6 +42,8 @@ export function getTypeName(type: string): string {
       return "HTML";
     case "text/rtf":
       return "リッチテキスト";
+    case "text/markdown":
+      return "Markdown";
     case "text/uri-list":
       return "URL";
     case "image/png":
@@ -54,6 +59,48 @@ export function getTypeName(type: string): string {
   }
 }
 
+/**
+ * 内容の種類に応じた表示名を取得（通常のテキストの場合はnull）
+ */
+export function getKindName(kind: ContentKind | undefined, detail?: string): string | null {
+  switch (kind) {
+    case "url":
+      return "URL";
+    case "email":
+      return "メールアドレス";
+    case "phone":
+      return "電話番号";
+    case "uuid":
+      return "UUID";
+    case "ip_address":
+      return detail === "v6" ? "IPv6アドレス" : "IPアドレス";
+    case "color":
+      return "カラーコード";
+    case "file_path":
+      return detail === "missing" ? "ファイルパス（存在しない）" : "ファイルパス";
+    case "data_url":
+      return "data URL";
+    case "json":
+      return "JSON";
+    case "yaml":
+      return "YAML";
+    case "xml":
+      return "XML";
+    case "html":
+      return "HTML";
+    case "rtf":
+      return "RTF";
+    case "sql":
+      return "SQL";
+    case "markdown":
+      return "Markdown";
+    case "code":
+      return detail ? `コード (${detail})` : "コード";
+    default:
+      return null;
+  }
+}
+
 /**
  * ファイル拡張子に応じた絵文字アイコンを取得
  */
@@ -147,6 +194,16 @@ export function getFileIcon(filename: string): string {
   }
 }
 
+/**
+ * バイト数を読みやすい単位で表示
+ */
+export function formatFileSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes}B`;
+  if (bytes < 1024 * 1024) return `${Math.round(bytes / 1024)}KB`;
+  if (bytes < 1024 * 1024 * 1024) return `${(bytes / 1024 / 1024).toFixed(1)}MB`;
+  return `${(bytes / 1024 / 1024 / 1024).toFixed(1)}GB`;
+}
+
 /**
  * ファイルリストテキストを解析してファイル情報を取得
  */